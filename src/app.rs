@@ -1,27 +1,57 @@
 use crate::{
-    AnyResult, CONFIG_PATH, Column, Plan, Priority, Progress, Task,
-    config::{self, Config, Order, UniqueTaskKeys},
-    event::MsEvent,
+    AnyResult, Column, Plan, Priority, Progress, Task,
+    board::{self, BoardState},
+    calendar::CalendarState,
+    config::{self, Config, DateFilter, ListSort, Order, SortKey, TaskSort, UniqueTaskKeys},
+    diff::PlanDiff,
+    event::{MsEvent, WatchControl},
+    export::ExportFormat,
     filter::{FilterType, SortType, UiColumn, UiTagFilter},
-    ui,
+    flow::{self, FlowFormat, FlowPoint},
+    group::{self, Group, GroupBy, TableRow},
+    history,
+    parse_xlsx::ParseError,
+    paths::Paths,
+    query::Query,
+    timeline::TimelineState,
+    ui, workload,
 };
 use ratatui::{
     DefaultTerminal,
-    crossterm::event::{self, Event, KeyCode, KeyEvent},
+    crossterm::event::{Event, KeyCode, KeyEvent},
     widgets::{ListState, TableState},
 };
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::io::Write;
 use tui_input::backend::crossterm::EventHandler;
 
 pub struct App {
     pub plan: Plan,
     pub config: Config,
+    pub paths: Paths,
     pub table_state: TableState,
     pub displayed_tasks: Vec<Task>,
     pub error_popup: Option<String>,
     pub input_mode: InputMode,
     pub filter_view: FilterView,
     pub selected_task: Option<usize>,
+    pub board: BoardState,
+    pub workload: TableState,
+    /// Person picked in the workload view, narrows the table without
+    /// touching the saved filter.
+    pub drill_down: Option<String>,
+    /// Mode the filter column was opened from, restored when it closes.
+    pub filter_return: Option<InputMode>,
+    pub calendar: CalendarState,
+    pub timeline: TimelineState,
+    pub flow: Option<Vec<FlowPoint>>,
+    /// Titles of the collapsed groups of the table.
+    pub collapsed: HashSet<String>,
+    /// Changes since the previous archived export and the scroll offset.
+    pub diff: Option<(PlanDiff, u16)>,
     pub event_rx: std::sync::mpsc::Receiver<MsEvent>,
+    pub watch: WatchControl,
 }
 pub struct FilterView {
     pub state: ListState,
@@ -31,27 +61,38 @@ pub struct FilterView {
 pub enum FilterViewMode {
     TagFilter(UiTagFilter, Column),
     TextFilter(tui_input::Input, Column),
+    DateFilter(tui_input::Input, Column),
+    Presets,
+    /// Name input for saving the active filter as a preset, or for renaming
+    /// the given preset.
+    PresetName(tui_input::Input, Option<String>),
     Columns,
 }
 pub enum InputMode {
     TableRow,
     FilterMode,
+    Board,
+    Diff,
+    Workload,
+    Dashboard,
+    /// Burndown and cumulative flow of the archived exports.
+    Flow,
+    Calendar,
+    Timeline,
+    /// Editing the query line below the table.
+    Query(tui_input::Input),
 }
 
 impl App {
-    pub fn new(plan: Plan, config: Config) -> Self {
-        let buckets = plan.tasks.iter().map(|t| &t.bucket);
-        let labels = plan.tasks.iter().flat_map(|t| &t.labels);
-        let people = plan.tasks.iter().flat_map(|t| &t.assigned_to);
-        let unique_task_keys = UniqueTaskKeys {
-            buckets: config::get_unique_strings(buckets),
-            labels: config::get_unique_strings(labels),
-            people: config::get_unique_strings(people),
-        };
+    pub fn new(plan: Plan, config: Config, paths: Paths) -> Self {
+        let unique_task_keys = UniqueTaskKeys::new(&plan.tasks);
+        let (event_rx, watch) = crate::event::setup(&config, &paths);
         let mut app = App {
             plan,
-            event_rx: crate::event::setup(&config),
+            event_rx,
+            watch,
             config,
+            paths,
             displayed_tasks: vec![],
             error_popup: None,
             table_state: TableState::new().with_selected(0),
@@ -62,6 +103,15 @@ impl App {
                 filter_mode: FilterViewMode::Columns,
             },
             selected_task: None,
+            board: BoardState::default(),
+            workload: TableState::new().with_selected(0),
+            drill_down: None,
+            filter_return: None,
+            calendar: CalendarState::default(),
+            timeline: TimelineState::default(),
+            flow: None,
+            collapsed: HashSet::new(),
+            diff: None,
         };
         app.set_filterd_tasks();
         app
@@ -73,52 +123,156 @@ impl App {
             let event = match event {
                 MsEvent::Crossterm(event) => event,
                 MsEvent::PlanMoved => {
-                    self.plan = Plan::from_path(crate::PLAN_PATH)?;
-                    self.add_error_msg("plan reloaded");
+                    self.reload_plan();
+                    continue;
+                }
+                MsEvent::WatchError(msg) => {
+                    self.add_error_msg(&msg);
                     continue;
                 }
             };
             if let Event::Key(key) = event? {
-                if let KeyCode::Char('q') = key.code {
+                if let KeyCode::Char('q') = key.code
+                    && !self.is_typing()
+                {
                     break;
                 } else if self.error_popup.is_some() {
                     if let KeyCode::Esc = key.code {
                         self.error_popup = None
                     }
                     continue;
-                } else if let KeyCode::Char('R') = key.code {
-                    self.plan = Plan::from_path(crate::PLAN_PATH)?;
+                } else if let KeyCode::Char('R') = key.code
+                    && !self.is_typing()
+                {
                     // self.selected_task = None;
                     // self.filter_view.ui_tag_filter = None;
-                    self.add_error_msg("plan reloaded");
+                    self.reload_plan();
                 }
                 match &self.input_mode {
                     InputMode::TableRow => self.run_table_row_mode(key),
                     InputMode::FilterMode => self.run_filter_mode(key),
+                    InputMode::Board => self.run_board_mode(key),
+                    InputMode::Diff => self.run_diff_mode(key),
+                    InputMode::Workload => self.run_workload_mode(key),
+                    InputMode::Flow => self.run_flow_mode(key),
+                    InputMode::Calendar => self.run_calendar_mode(key),
+                    InputMode::Timeline => self.run_timeline_mode(key),
+                    InputMode::Dashboard => {
+                        if let KeyCode::Esc = key.code {
+                            self.input_mode = InputMode::TableRow;
+                        }
+                        Ok(())
+                    }
+                    InputMode::Query(_) => self.run_query_mode(key),
                 }?;
                 self.set_filterd_tasks();
             }
         }
         Ok(())
     }
+    pub fn open_filter(&mut self) {
+        let previous = std::mem::replace(&mut self.input_mode, InputMode::FilterMode);
+        self.filter_return = Some(previous);
+    }
+    /// Whether keys go to a text input, so the global bindings don't apply.
+    pub fn is_typing(&self) -> bool {
+        match self.input_mode {
+            InputMode::Query(_) => true,
+            InputMode::FilterMode => matches!(
+                self.filter_view.filter_mode,
+                FilterViewMode::TextFilter(..)
+                    | FilterViewMode::DateFilter(..)
+                    | FilterViewMode::PresetName(..)
+            ),
+            _ => false,
+        }
+    }
+    pub fn run_query_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let InputMode::Query(input) = &mut self.input_mode else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => self.input_mode = InputMode::TableRow,
+            KeyCode::Enter => {
+                let query = match input.value().trim() {
+                    "" => Ok(None),
+                    text => text.parse::<Query>().map(Some),
+                };
+                match query {
+                    Ok(query) => {
+                        self.config.filter.query = query;
+                        self.config.to_file(&self.paths.config)?;
+                        self.input_mode = InputMode::TableRow;
+                    }
+                    Err(e) => self.add_error_msg(&format!("query: {e}")),
+                }
+            }
+            _ => {
+                input.handle_event(&Event::Key(key));
+            }
+        }
+        Ok(())
+    }
     pub fn run_table_row_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
         match (key.code, self.selected_task) {
             (KeyCode::Char('j'), None) => self.table_state.select_next(),
             (KeyCode::Char('k'), None) => self.table_state.select_previous(),
-            (KeyCode::Char('f'), None) => {
-                self.input_mode = InputMode::FilterMode;
+            (KeyCode::Char('f'), None) => self.open_filter(),
+            (KeyCode::Char('b'), None) => {
+                self.input_mode = InputMode::Board;
             }
-            (KeyCode::Char(' '), None) | (KeyCode::Char('o'), None) => {
-                if let Some(i) = self.table_state.selected() {
-                    self.selected_task = Some(i)
+            (KeyCode::Char('D'), None) => self.show_diff(),
+            (KeyCode::Char('w'), None) => self.input_mode = InputMode::Workload,
+            (KeyCode::Char('d'), None) => self.input_mode = InputMode::Dashboard,
+            (KeyCode::Char('C'), None) => self.input_mode = InputMode::Calendar,
+            (KeyCode::Char('t'), None) => self.input_mode = InputMode::Timeline,
+            (KeyCode::Char('c'), None) => {
+                let series = self
+                    .paths
+                    .history()
+                    .map_err(Into::into)
+                    .and_then(|dir| flow::series(&dir, &self.plan.id, &self.config));
+                match series {
+                    Ok(points) => {
+                        self.flow = Some(points);
+                        self.input_mode = InputMode::Flow;
+                    }
+                    Err(e) => self.add_error_msg(&format!("reading the history failed: {e}")),
                 }
             }
+            (KeyCode::Char('/'), None) => {
+                let query = self.config.filter.query.as_ref();
+                let text = query.map(ToString::to_string).unwrap_or_default();
+                self.input_mode = InputMode::Query(tui_input::Input::new(text));
+            }
+            (KeyCode::Char(' '), None) | (KeyCode::Char('o'), None) => match self.selected_row() {
+                Some(TableRow::Header(_)) => self.toggle_group(),
+                Some(TableRow::Task(i, _)) => self.selected_task = Some(i),
+                None => (),
+            },
+            (KeyCode::Char('g'), None) => {
+                self.config.group_by = GroupBy::next(self.config.group_by);
+                self.collapsed.clear();
+                self.table_state.select_first();
+                self.config.to_file(&self.paths.config)?;
+            }
+            (KeyCode::Char('z'), None) => self.toggle_group(),
+            (KeyCode::Char('Z'), None) => {
+                let groups = self.groups().unwrap_or_default();
+                let titles = groups.into_iter().filter(|g| !g.tasks.is_empty());
+                let titles: HashSet<String> = titles.map(|g| g.title).collect();
+                self.collapsed = if self.collapsed.is_superset(&titles) {
+                    HashSet::new()
+                } else {
+                    titles
+                };
+            }
             (KeyCode::Char('F'), None) => {
                 self.config.filter.filter_ids = !self.config.filter.filter_ids;
-                self.config.to_file(crate::CONFIG_PATH)?;
+                self.config.to_file(&self.paths.config)?;
             }
             (KeyCode::Char('i'), None) => {
-                if let Some(i) = self.table_state.selected() {
+                if let Some(i) = self.selected_row_task() {
                     let task = &self.displayed_tasks[i];
                     if let Some(i) = self.config.filter.ids.iter().position(|id| id == &task.id) {
                         self.config.filter.ids.remove(i);
@@ -127,23 +281,245 @@ impl App {
                     }
                 }
             }
+            (KeyCode::Char('E'), None) => self.export(self.config.export.format),
+            (KeyCode::Char('I'), None) => self.export(ExportFormat::Ics),
+            (KeyCode::Char('W'), None) => {
+                let msg = match self.watch.toggle() {
+                    true => "watching for new exports paused",
+                    false => "watching for new exports resumed",
+                };
+                self.add_error_msg(msg);
+            }
+            (KeyCode::Char('L'), _) => {
+                if let Some(i) = self.selected_task.or(self.selected_row_task()) {
+                    let url = &self.displayed_tasks[i].to_url(&self.plan.id);
+                    webbrowser::open(url)?;
+                }
+            }
+            (KeyCode::Esc, Some(_)) => self.selected_task = None,
+            (KeyCode::Esc, None) => self.drill_down = None,
+            _ => (),
+        }
+        Ok(())
+    }
+    /// The groups of the table, none when it isn't grouped.
+    pub fn groups(&self) -> Option<Vec<Group>> {
+        let group_by = self.config.group_by?;
+        Some(group::groups(&self.displayed_tasks, group_by))
+    }
+    pub fn table_rows(&self, groups: Option<&[Group]>) -> Vec<TableRow> {
+        group::table_rows(groups, self.displayed_tasks.len(), &self.collapsed)
+    }
+    pub fn selected_row(&self) -> Option<TableRow> {
+        let rows = self.table_rows(self.groups().as_deref());
+        rows.get(self.table_state.selected()?).copied()
+    }
+    /// Index in the displayed tasks of the task on the selected row.
+    pub fn selected_row_task(&self) -> Option<usize> {
+        match self.selected_row()? {
+            TableRow::Task(i, _) => Some(i),
+            TableRow::Header(_) => None,
+        }
+    }
+    /// Collapses or expands the group of the selected row.
+    fn toggle_group(&mut self) {
+        let Some(groups) = self.groups() else {
+            return;
+        };
+        let (TableRow::Header(g) | TableRow::Task(_, g)) = match self.selected_row() {
+            Some(row) => row,
+            None => return,
+        };
+        let title = &groups[g].title;
+        if !self.collapsed.remove(title) {
+            self.collapsed.insert(title.clone());
+            // keep the cursor on the header of the collapsed group
+            let rows = self.table_rows(Some(&groups));
+            let header = rows.iter().position(|r| *r == TableRow::Header(g));
+            self.table_state.select(header);
+        }
+    }
+    pub fn run_board_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let columns = board::columns(&self.displayed_tasks, self.board.group);
+        let selected = self.board.selected(&columns);
+        match (key.code, self.selected_task) {
+            (KeyCode::Char('h'), None) => self.board.select_left(),
+            (KeyCode::Char('l'), None) => self.board.select_right(),
+            (KeyCode::Char('k'), None) => self.board.select_up(),
+            (KeyCode::Char('j'), None) => self.board.select_down(),
+            (KeyCode::Char('g'), None) => {
+                self.board.group = self.board.group.next();
+                self.board.column = 0;
+                self.board.row = 0;
+            }
+            (KeyCode::Char('f'), None) => self.open_filter(),
+            (KeyCode::Char(' '), None) | (KeyCode::Char('o'), None) => {
+                self.selected_task = selected;
+            }
             (KeyCode::Char('L'), _) => {
-                if let Some(i) = self.table_state.selected() {
+                if let Some(i) = self.selected_task.or(selected) {
                     let url = &self.displayed_tasks[i].to_url(&self.plan.id);
                     webbrowser::open(url)?;
                 }
             }
             (KeyCode::Esc, Some(_)) => self.selected_task = None,
+            (KeyCode::Esc, None) => self.input_mode = InputMode::TableRow,
             _ => (),
         }
+        self.board.clamp(&columns);
+        Ok(())
+    }
+    pub fn run_workload_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        match key.code {
+            KeyCode::Char('j') => self.workload.select_next(),
+            KeyCode::Char('k') => self.workload.select_previous(),
+            KeyCode::Enter | KeyCode::Char('o') => {
+                let today = chrono::Local::now().date_naive();
+                let people = &self.filter_view.unique_task_keys.people;
+                let loads = workload::workloads(&self.workload_tasks(), people, today);
+                if let Some(load) = self.workload.selected().and_then(|i| loads.get(i)) {
+                    self.drill_down = Some(load.person.clone());
+                    self.table_state.select_first();
+                    self.input_mode = InputMode::TableRow;
+                }
+            }
+            KeyCode::Esc => self.input_mode = InputMode::TableRow,
+            _ => (),
+        }
+        Ok(())
+    }
+    pub fn run_calendar_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let calendar = &mut self.calendar;
+        match (key.code, self.selected_task) {
+            (KeyCode::Char('h'), None) => calendar.move_days(-1),
+            (KeyCode::Char('l'), None) => calendar.move_days(1),
+            (KeyCode::Char('k'), None) => calendar.move_days(-7),
+            (KeyCode::Char('j'), None) => calendar.move_days(7),
+            (KeyCode::Char('H'), None) => calendar.move_months(-1),
+            (KeyCode::Char('L'), None) => calendar.move_months(1),
+            (KeyCode::Char('n'), None) => calendar.cycle_task(&self.displayed_tasks, true),
+            (KeyCode::Char('N'), None) => calendar.cycle_task(&self.displayed_tasks, false),
+            (KeyCode::Char('s'), None) => {
+                calendar.spans = !calendar.spans;
+                calendar.row = 0;
+            }
+            (KeyCode::Char('t'), None) => {
+                *calendar = CalendarState {
+                    spans: calendar.spans,
+                    ..Default::default()
+                }
+            }
+            (KeyCode::Char(' '), None) | (KeyCode::Char('o'), None) => {
+                self.selected_task = calendar.selected(&self.displayed_tasks);
+            }
+            (KeyCode::Esc, Some(_)) => self.selected_task = None,
+            (KeyCode::Esc, None) => self.input_mode = InputMode::TableRow,
+            _ => (),
+        }
+        Ok(())
+    }
+    pub fn run_timeline_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let timeline = &mut self.timeline;
+        match key.code {
+            KeyCode::Char('h') => timeline.scroll_time(-1),
+            KeyCode::Char('l') => timeline.scroll_time(1),
+            KeyCode::Char('H') => timeline.scroll_time(-10),
+            KeyCode::Char('L') => timeline.scroll_time(10),
+            KeyCode::Char('j') => timeline.scroll = timeline.scroll.saturating_add(1),
+            KeyCode::Char('k') => timeline.scroll = timeline.scroll.saturating_sub(1),
+            KeyCode::Char('z') => timeline.zoom(),
+            KeyCode::Char('g') => timeline.toggle_group(),
+            KeyCode::Char('t') => timeline.go_to_today(),
+            KeyCode::Esc => self.input_mode = InputMode::TableRow,
+            _ => (),
+        }
+        Ok(())
+    }
+    pub fn run_flow_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let format = match key.code {
+            KeyCode::Char('c') => FlowFormat::Csv,
+            KeyCode::Char('s') => FlowFormat::Svg,
+            KeyCode::Esc => {
+                self.flow = None;
+                self.input_mode = InputMode::TableRow;
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+        if let Some(points) = &self.flow {
+            let path = flow::export_path(format);
+            match write_file(&path, |file| flow::export(file, points, format)) {
+                Ok(()) => self.add_error_msg(&format!("exported the charts to {}", path.display())),
+                Err(e) => self.add_error_msg(&format!(
+                    "exporting the charts to {} failed: {e}",
+                    path.display()
+                )),
+            }
+        }
+        Ok(())
+    }
+    /// Writes the displayed tasks to the export file of the format.
+    pub fn export(&mut self, format: ExportFormat) {
+        let path = self.config.export.path_for(format);
+        let written = write_file(&path, |file| {
+            crate::export::export(
+                file,
+                &self.displayed_tasks,
+                &self.config.export.columns,
+                format,
+                &self.plan.id,
+            )
+        });
+        match written {
+            Ok(()) => self.add_error_msg(&format!(
+                "exported {} tasks to {}",
+                self.displayed_tasks.len(),
+                path.display()
+            )),
+            Err(e) => self.add_error_msg(&format!("exporting to {} failed: {e}", path.display())),
+        }
+    }
+    pub fn show_diff(&mut self) {
+        let previous = self
+            .paths
+            .history()
+            .and_then(|dir| history::previous(&dir, &self.plan));
+        match previous {
+            Ok(Some(snapshot)) => match snapshot.load() {
+                Ok(previous) => {
+                    self.diff = Some((PlanDiff::new(&previous, &self.plan), 0));
+                    self.input_mode = InputMode::Diff;
+                }
+                Err(e) => {
+                    self.add_error_msg(&format!("reading {} failed: {e}", snapshot.path.display()))
+                }
+            },
+            Ok(None) => self.add_error_msg("no earlier export of this plan in the history"),
+            Err(e) => self.add_error_msg(&format!("reading the history failed: {e}")),
+        }
+    }
+    pub fn run_diff_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
+        if let Some((_, scroll)) = &mut self.diff {
+            match key.code {
+                KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+                KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                _ => (),
+            }
+        }
+        if let KeyCode::Esc = key.code {
+            self.diff = None;
+            self.input_mode = InputMode::TableRow;
+        }
         Ok(())
     }
     pub fn run_filter_mode(&mut self, key: KeyEvent) -> AnyResult<()> {
         match self.filter_view.filter_mode {
             FilterViewMode::Columns => {
                 let selected = self.filter_view.state.selected();
-                let ui_col = selected
-                    .map(|i| UiColumn::all(&self.config.filter, &self.config.sort).remove(i));
+                let ui_col = selected.map(|i| {
+                    UiColumn::all(&self.config.filter, &self.config.sort, &self.config.table)
+                        .remove(i)
+                });
                 self.run_columns_filter(key, ui_col)?
             }
             FilterViewMode::TagFilter(ref ui_tag_filter, c) => {
@@ -162,12 +538,86 @@ impl App {
                         _ => (),
                         // Column::Description => self.config.filter.description = text.clone(),
                     }
-                    self.config.to_file(crate::CONFIG_PATH)?;
+                    self.config.to_file(&self.paths.config)?;
+                }
+            },
+            FilterViewMode::DateFilter(ref mut input, c) => match key.code {
+                KeyCode::Esc => self.filter_view.filter_mode = FilterViewMode::Columns,
+                KeyCode::Enter => match input.value().parse::<DateFilter>() {
+                    Ok(date_filter) => {
+                        if let Some(df) = self.config.filter.date_filter_mut(c) {
+                            *df = date_filter;
+                        }
+                        self.config.to_file(&self.paths.config)?;
+                        self.filter_view.filter_mode = FilterViewMode::Columns;
+                    }
+                    Err(e) => self.add_error_msg(&e.to_string()),
+                },
+                _ => {
+                    input.handle_event(&Event::Key(key));
+                }
+            },
+            FilterViewMode::Presets => self.run_presets(key)?,
+            FilterViewMode::PresetName(ref mut input, ref old) => match key.code {
+                KeyCode::Esc => self.filter_view.filter_mode = FilterViewMode::Presets,
+                KeyCode::Enter => {
+                    let name = input.value().trim().to_string();
+                    let result = match old {
+                        _ if name.is_empty() => Err("preset name is empty".into()),
+                        Some(old) => self.config.rename_preset(&old.clone(), &name),
+                        None => {
+                            self.config.save_preset(&name);
+                            Ok(())
+                        }
+                    };
+                    match result {
+                        Ok(()) => {
+                            self.config.to_file(&self.paths.config)?;
+                            self.filter_view.filter_mode = FilterViewMode::Presets;
+                        }
+                        Err(e) => self.add_error_msg(&e.to_string()),
+                    }
+                }
+                _ => {
+                    input.handle_event(&Event::Key(key));
                 }
             },
         }
         Ok(())
     }
+    pub fn run_presets(&mut self, key: KeyEvent) -> AnyResult<()> {
+        let selected = self
+            .filter_view
+            .state
+            .selected()
+            .and_then(|i| self.config.presets.keys().nth(i).cloned());
+        match (key.code, selected) {
+            (KeyCode::Char('j'), _) => self.filter_view.state.select_next(),
+            (KeyCode::Char('k'), _) => self.filter_view.state.select_previous(),
+            (KeyCode::Esc, _) => {
+                self.filter_view.filter_mode = FilterViewMode::Columns;
+                self.filter_view.state.select_first();
+            }
+            (KeyCode::Char('S'), _) => {
+                self.filter_view.filter_mode =
+                    FilterViewMode::PresetName(tui_input::Input::default(), None);
+            }
+            (KeyCode::Enter, Some(name)) | (KeyCode::Char(' '), Some(name)) => {
+                self.config.apply_preset(&name)?;
+                self.config.to_file(&self.paths.config)?;
+            }
+            (KeyCode::Char('r'), Some(name)) => {
+                self.filter_view.filter_mode =
+                    FilterViewMode::PresetName(tui_input::Input::new(name.clone()), Some(name));
+            }
+            (KeyCode::Char('d'), Some(name)) => {
+                self.config.presets.remove(&name);
+                self.config.to_file(&self.paths.config)?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
     pub fn run_tag_filter(
         &mut self,
         key: KeyEvent,
@@ -219,7 +669,7 @@ impl App {
             Column::Priority => self.config.filter.priority = ui_tag_filter.clone().try_into()?,
             _ => todo!(),
         };
-        self.config.to_file(crate::CONFIG_PATH)?;
+        self.config.to_file(&self.paths.config)?;
         Ok(())
     }
     pub fn run_columns_filter(
@@ -231,27 +681,38 @@ impl App {
             (KeyCode::Char('j'), _) => self.filter_view.state.select_next(),
             (KeyCode::Char('k'), _) => self.filter_view.state.select_previous(),
             (KeyCode::Esc, _) => {
-                self.input_mode = InputMode::TableRow;
+                self.input_mode = self.filter_return.take().unwrap_or(InputMode::TableRow);
+                self.filter_view.state.select_first();
+            }
+            (KeyCode::Char('p'), _) => {
+                self.filter_view.filter_mode = FilterViewMode::Presets;
                 self.filter_view.state.select_first();
             }
+            (KeyCode::Char('v'), Some(ui_col)) => {
+                self.config.table.toggle(ui_col.column);
+                self.config.to_file(&self.paths.config)?;
+            }
             (KeyCode::Char('u'), Some(ui_col)) => {
                 self.config.filter.reset_filter(ui_col.column);
-                self.config.to_file(CONFIG_PATH)?;
+                self.config.to_file(&self.paths.config)?;
             }
             (KeyCode::Char('s'), Some(ui_col)) => {
                 match ui_col.sort {
-                    SortType::Sorted(sort) => {
-                        self.config.sort.order = match sort {
-                            Order::Desc => Order::Asc,
-                            Order::Asc => Order::Desc,
-                        }
-                    }
-                    SortType::Unsorted => {
-                        self.config.sort.column = ui_col.column;
-                    }
-                    SortType::Nil => self.add_error_msg("sort is not implemented for the column"),
+                    SortType::Sorted(..) => self.config.sort.toggle_order(ui_col.column),
+                    SortType::Unsorted => self.config.sort.set_primary(ui_col.column),
+                }
+                self.config.to_file(&self.paths.config)?;
+            }
+            (KeyCode::Char(c @ ('S' | 'x' | 'K' | 'J' | 'm')), Some(ui_col)) => {
+                let sort = &mut self.config.sort;
+                match c {
+                    'S' => sort.add(ui_col.column),
+                    'x' => sort.remove(ui_col.column),
+                    'K' => sort.promote(ui_col.column),
+                    'J' => sort.demote(ui_col.column),
+                    _ => sort.cycle_list_sort(ui_col.column),
                 }
-                self.config.to_file(crate::CONFIG_PATH)?;
+                self.config.to_file(&self.paths.config)?;
             }
             (KeyCode::Char(' '), Some(ui_col)) | (KeyCode::Char('o'), Some(ui_col)) => {
                 match FilterType::new(ui_col.column, &self.config.filter) {
@@ -283,6 +744,16 @@ impl App {
                         self.filter_view.filter_mode =
                             FilterViewMode::TextFilter(tui_input::Input::new(text), ui_col.column)
                     }
+                    FilterType::Date(_) => {
+                        let text = self
+                            .config
+                            .filter
+                            .date_filter_mut(ui_col.column)
+                            .map(|df| df.to_string())
+                            .unwrap_or_default();
+                        self.filter_view.filter_mode =
+                            FilterViewMode::DateFilter(tui_input::Input::new(text), ui_col.column)
+                    }
                     FilterType::Nil => {
                         self.add_error_msg("No filtering implemented for this column")
                     }
@@ -295,6 +766,9 @@ impl App {
 
     pub fn set_filterd_tasks(&mut self) {
         let mut filtered_tasks = filter_tasks(&self.config, &self.plan.tasks);
+        if let Some(person) = &self.drill_down {
+            filtered_tasks.retain(|t| t.assigned_to.contains(person));
+        }
         sort_tasks(&self.config, &mut filtered_tasks);
         // filtered_tasks = filtered_tasks.into_iter().take(3).collect();
         self.displayed_tasks = filtered_tasks;
@@ -304,6 +778,41 @@ impl App {
         //     self.displayed_tasks.len()
         // ));
     }
+    /// The filtered tasks without the assignee filter, so the workload view
+    /// shows everyone.
+    pub fn workload_tasks(&self) -> Vec<Task> {
+        let mut config = self.config.clone();
+        config.filter.assigned_to = Default::default();
+        filter_tasks(&config, &self.plan.tasks)
+    }
+    pub fn reload_plan(&mut self) {
+        match Plan::from_path_lenient(&self.paths.plan) {
+            Ok((plan, warnings)) => {
+                self.plan = plan;
+                self.filter_view.unique_task_keys = UniqueTaskKeys::new(&self.plan.tasks);
+                self.add_error_msg("plan reloaded");
+                self.add_parse_warnings(&warnings);
+                let archived = self
+                    .paths
+                    .history()
+                    .and_then(|dir| history::archive(&self.plan, &self.paths.plan, &dir));
+                if let Err(e) = archived {
+                    self.add_error_msg(&format!("archiving the plan failed: {e}"));
+                }
+            }
+            Err(e) => self.add_error_msg(&format!("plan reload failed: {e}")),
+        }
+        self.set_filterd_tasks();
+    }
+    pub fn add_parse_warnings(&mut self, warnings: &[ParseError]) {
+        if warnings.is_empty() {
+            return;
+        }
+        self.add_error_msg(&format!("{} parse warnings:", warnings.len()));
+        for warning in warnings {
+            self.add_error_msg(&format!("  {warning}"));
+        }
+    }
     pub fn add_error_msg(&mut self, s: &str) {
         let text = if let Some(text) = &self.error_popup {
             text.to_owned() + "\n" + s
@@ -314,7 +823,18 @@ impl App {
     }
 }
 
-fn filter_tasks(config: &Config, tasks: &[Task]) -> Vec<Task> {
+/// Creates the file and hands it to `write`, flushing it afterwards.
+fn write_file(
+    path: &std::path::Path,
+    write: impl FnOnce(&mut std::io::BufWriter<std::fs::File>) -> AnyResult<()>,
+) -> AnyResult<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write(&mut file)?;
+    file.flush()?;
+    Ok(())
+}
+
+pub fn filter_tasks(config: &Config, tasks: &[Task]) -> Vec<Task> {
     let tasks = tasks.iter();
     let tasks =
         tasks.filter(|&task| !config.filter.filter_ids || config.filter.ids.contains(&task.id));
@@ -327,24 +847,59 @@ fn filter_tasks(config: &Config, tasks: &[Task]) -> Vec<Task> {
     let tasks = tasks.filter(|task| config::no_case_contains(&config.filter.name, &task.name));
     let tasks = tasks
         .filter(|task| config::no_case_contains(&config.filter.description, &task.description));
+    let tasks = tasks.filter(|task| config.filter.create_date.filter(Some(task.create_date)));
+    let tasks = tasks.filter(|task| config.filter.start_date.filter(task.start_date));
+    let today = chrono::Local::now().date_naive();
+    let tasks = tasks.filter(|task| config.filter.deadline.filter_deadline(task, today));
+    let tasks = tasks.filter(|task| config.filter.complete_date.filter(task.complete_date));
+    let tasks = tasks.filter(|task| config.filter.query.as_ref().is_none_or(|q| q.matches(task)));
 
     tasks.cloned().collect()
 }
-fn sort_tasks(config: &Config, tasks: &mut [Task]) {
+/// Stable sort by every key in turn, later keys only break ties.
+pub fn sort_tasks(config: &Config, tasks: &mut [Task]) {
+    let sort = &config.sort;
+    tasks.sort_by(|a, b| {
+        sort.keys.iter().fold(Ordering::Equal, |ord, key| {
+            ord.then_with(|| match key.order {
+                Order::Desc => compare_by(sort, key, a, b),
+                Order::Asc => compare_by(sort, key, a, b).reverse(),
+            })
+        })
+    });
+}
+fn compare_by(sort: &TaskSort, key: &SortKey, a: &Task, b: &Task) -> Ordering {
     use Column as C;
-    match config.sort.column {
-        C::Name => tasks.sort_by_key(|task| task.name.clone()),
-        C::Description => tasks.sort_by_key(|task| task.description.clone()),
-        C::Deadline => tasks.sort_by_key(|task| task.deadline),
-        C::CreateDate => tasks.sort_by_key(|task| task.create_date),
-        C::StartDate => tasks.sort_by_key(|task| task.start_date),
-        C::CompleteDate => tasks.sort_by_key(|task| task.complete_date),
-        C::Priority => tasks.sort_by_key(|task| task.priority.clone()),
-        C::Progress => tasks.sort_by_key(|task| task.progress.clone()),
-        C::Bucket => tasks.sort_by_key(|task| task.bucket.clone()),
-        C::Labels | C::AssignedTo => panic!("unable to sort those"),
+    match key.column {
+        C::Name => a.name.cmp(&b.name),
+        C::Description => a.description.cmp(&b.description),
+        C::Deadline => a.deadline.cmp(&b.deadline),
+        C::CreateDate => a.create_date.cmp(&b.create_date),
+        C::StartDate => a.start_date.cmp(&b.start_date),
+        C::CompleteDate => a.complete_date.cmp(&b.complete_date),
+        C::Priority => a.priority.cmp(&b.priority),
+        C::Progress => a.progress.cmp(&b.progress),
+        C::Bucket => a.bucket.cmp(&b.bucket),
+        C::CreatedBy => a.created_by.cmp(&b.created_by),
+        C::Late => a.late.cmp(&b.late),
+        C::Items => a.items_completed.cmp(&b.items_completed),
+        C::Labels => compare_lists(key.by, &sort.label_ranking, &a.labels, &b.labels),
+        C::AssignedTo => {
+            compare_lists(key.by, &sort.people_ranking, &a.assigned_to, &b.assigned_to)
+        }
     }
-    if matches!(config.sort.order, config::Order::Asc) {
-        tasks.reverse();
+}
+fn compare_lists(by: ListSort, ranking: &[String], a: &[String], b: &[String]) -> Ordering {
+    // unranked entries share the rank after the last ranked one, empty lists come last
+    let rank = |list: &[String]| {
+        list.iter()
+            .map(|s| ranking.iter().position(|r| r == s).unwrap_or(ranking.len()))
+            .min()
+            .unwrap_or(ranking.len() + 1)
+    };
+    match by {
+        ListSort::First => a.first().cmp(&b.first()),
+        ListSort::Count => a.len().cmp(&b.len()),
+        ListSort::Ranking => rank(a).cmp(&rank(b)),
     }
 }
@@ -0,0 +1,136 @@
+use chrono::NaiveDate;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, List, ListState},
+};
+
+use crate::{
+    Task,
+    app::App,
+    group::{self, Group, GroupBy},
+    ui,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoardGroup {
+    #[default]
+    Bucket,
+    Progress,
+    Priority,
+}
+impl BoardGroup {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Bucket => Self::Progress,
+            Self::Progress => Self::Priority,
+            Self::Priority => Self::Bucket,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BoardState {
+    pub group: BoardGroup,
+    pub column: usize,
+    pub row: usize,
+}
+impl BoardState {
+    /// Keeps the selection inside the board after the columns changed.
+    pub fn clamp(&mut self, columns: &[Group]) {
+        self.column = self.column.min(columns.len().saturating_sub(1));
+        let rows = columns.get(self.column).map_or(0, |c| c.tasks.len());
+        self.row = self.row.min(rows.saturating_sub(1));
+    }
+    pub fn select_left(&mut self) {
+        self.column = self.column.saturating_sub(1);
+    }
+    pub fn select_right(&mut self) {
+        self.column = self.column.saturating_add(1);
+    }
+    pub fn select_up(&mut self) {
+        self.row = self.row.saturating_sub(1);
+    }
+    pub fn select_down(&mut self) {
+        self.row = self.row.saturating_add(1);
+    }
+    /// Index in `tasks` of the selected card.
+    pub fn selected(&self, columns: &[Group]) -> Option<usize> {
+        columns.get(self.column)?.tasks.get(self.row).copied()
+    }
+}
+
+impl From<BoardGroup> for GroupBy {
+    fn from(value: BoardGroup) -> Self {
+        match value {
+            BoardGroup::Bucket => Self::Bucket,
+            BoardGroup::Progress => Self::Progress,
+            BoardGroup::Priority => Self::Priority,
+        }
+    }
+}
+
+pub fn columns(tasks: &[Task], group: BoardGroup) -> Vec<Group> {
+    group::groups(tasks, group.into())
+}
+
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|part| part.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn card(task: &Task, width: u16, today: NaiveDate) -> Text<'static> {
+    let width = width.saturating_sub(2) as usize;
+    let name: String = if task.name.chars().count() > width {
+        let mut name: String = task.name.chars().take(width.saturating_sub(1)).collect();
+        name.push('…');
+        name
+    } else {
+        task.name.clone()
+    };
+    let people: Vec<String> = task.assigned_to.iter().map(|p| initials(p)).collect();
+    let mut details: Vec<Span> = vec![Span::from(people.join(" ")).yellow()];
+    if let Some((done, all)) = task.items_completed {
+        details.push(Span::from(format!(" ☑ {done}/{all}")));
+    }
+    let mut text = Text::from(Line::from(name).add_modifier(Modifier::BOLD));
+    text.push_line(Line::from_iter(details));
+    text.extend(ui::table::deadline_text(task, today));
+    text.push_line(Line::from("─".repeat(width)).dark_gray());
+    text
+}
+
+pub fn render_board(app: &mut App, f: &mut Frame, area: Rect) {
+    let columns = columns(&app.displayed_tasks, app.board.group);
+    app.board.clamp(&columns);
+    let title = format!("board: {:?} (g: group by, h/j/k/l: move)", app.board.group);
+    let block = Block::bordered().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if columns.is_empty() {
+        return;
+    }
+    let today = chrono::Local::now().date_naive();
+    let areas = Layout::horizontal(vec![Constraint::Fill(1); columns.len()]).split(inner);
+    for (i, (column, &area)) in columns.iter().zip(areas.iter()).enumerate() {
+        let selected = i == app.board.column;
+        let cards = column
+            .tasks
+            .iter()
+            .map(|&t| card(&app.displayed_tasks[t], area.width, today));
+        let mut block =
+            Block::bordered().title(format!("{} ({})", column.title, column.tasks.len()));
+        if selected {
+            block = block.border_type(BorderType::Thick);
+        }
+        let list = List::new(cards)
+            .block(block)
+            .highlight_style(Style::new().bg(Color::DarkGray));
+        let mut state = ListState::default().with_selected(selected.then_some(app.board.row));
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
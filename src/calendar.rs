@@ -0,0 +1,145 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, List, ListState, Paragraph},
+};
+
+use crate::{Progress, Task, app::App, group};
+
+pub struct CalendarState {
+    pub day: NaiveDate,
+    /// Index of the selected task among the tasks of the day.
+    pub row: usize,
+    /// Also place tasks on every day from their start date to their deadline.
+    pub spans: bool,
+}
+impl Default for CalendarState {
+    fn default() -> Self {
+        Self {
+            day: chrono::Local::now().date_naive(),
+            row: 0,
+            spans: false,
+        }
+    }
+}
+impl CalendarState {
+    pub fn move_days(&mut self, days: i64) {
+        self.day += Duration::days(days);
+        self.row = 0;
+    }
+    pub fn move_months(&mut self, months: i32) {
+        let step = Months::new(months.unsigned_abs());
+        let day = if months < 0 {
+            self.day.checked_sub_months(step)
+        } else {
+            self.day.checked_add_months(step)
+        };
+        self.day = day.unwrap_or(self.day);
+        self.row = 0;
+    }
+    /// Selects the next task of the day, or the previous one, wrapping around.
+    pub fn cycle_task(&mut self, tasks: &[Task], forward: bool) {
+        let count = on_day(tasks, self.day, self.spans).len();
+        if count > 0 {
+            self.row = if forward {
+                (self.row + 1) % count
+            } else {
+                (self.row + count - 1) % count
+            };
+        }
+    }
+    /// Index in `tasks` of the selected task.
+    pub fn selected(&self, tasks: &[Task]) -> Option<usize> {
+        on_day(tasks, self.day, self.spans).get(self.row).copied()
+    }
+}
+
+/// Indexes of the tasks due on the day, or running on it when showing spans.
+pub fn on_day(tasks: &[Task], day: NaiveDate, spans: bool) -> Vec<usize> {
+    (0..tasks.len())
+        .filter(|&i| {
+            let task = &tasks[i];
+            match (spans, task.start_date, task.deadline) {
+                (_, _, Some(deadline)) if deadline == day => true,
+                (true, Some(start), Some(deadline)) => start <= day && day <= deadline,
+                (true, Some(start), None) => start == day,
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+fn first_of_month(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+pub fn render_calendar(app: &mut App, f: &mut Frame, area: Rect) {
+    let state = &app.calendar;
+    let today = chrono::Local::now().date_naive();
+    let tasks = &app.displayed_tasks;
+    let month = first_of_month(state.day);
+    let title = format!(
+        "{} (h/l: day, j/k: week, H/L: month, n/N: task, s: spans {}, t: today)",
+        month.format("%B %Y"),
+        if state.spans { "on" } else { "off" }
+    );
+    let block = Block::bordered().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [grid_area, day_area] =
+        Layout::horizontal([Constraint::Fill(3), Constraint::Fill(1)]).areas(inner);
+
+    let start = group::week_start(month);
+    let next_month = month + Months::new(1);
+    let weeks = ((next_month - start).num_days() + 6) / 7;
+    let [header_area, weeks_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(grid_area);
+    let columns = Layout::horizontal([Constraint::Fill(1); 7]);
+    for (name, area) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        .into_iter()
+        .zip(columns.split(header_area).iter())
+    {
+        f.render_widget(Line::from(name).bold().centered(), *area);
+    }
+    let rows = Layout::vertical(vec![Constraint::Fill(1); weeks as usize]).split(weeks_area);
+    for (week, row) in rows.iter().enumerate() {
+        for (weekday, &cell) in columns.split(*row).iter().enumerate() {
+            let day = start + Duration::days(week as i64 * 7 + weekday as i64);
+            let mut block = Block::bordered().title(day.day().to_string());
+            if day == state.day {
+                block = block.border_type(BorderType::Thick);
+            }
+            if day == today {
+                block = block.title_style(Style::new().yellow().add_modifier(Modifier::BOLD));
+            }
+            if day.month() != month.month() {
+                block = block.dark_gray();
+            }
+            let width = cell.width.saturating_sub(2) as usize;
+            let lines = on_day(tasks, day, state.spans).into_iter().map(|i| {
+                let task = &tasks[i];
+                let name: String = task.name.chars().take(width).collect();
+                let line = Line::from(name);
+                if task.progress == Progress::Done {
+                    line.dark_gray()
+                } else if task.is_overdue(today) {
+                    line.light_red()
+                } else {
+                    line
+                }
+            });
+            f.render_widget(Paragraph::new(Text::from_iter(lines)).block(block), cell);
+        }
+    }
+
+    let day_tasks = on_day(tasks, state.day, state.spans);
+    let items = day_tasks.iter().map(|&i| tasks[i].name.clone());
+    let list = List::new(items)
+        .block(Block::bordered().title(state.day.format("%A %Y-%m-%d").to_string()))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default().with_selected(Some(state.row));
+    f.render_stateful_widget(list, day_area, &mut list_state);
+}
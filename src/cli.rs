@@ -0,0 +1,342 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    AnyResult, Column, Plan, Priority, Progress, SimpleError, Task,
+    app::{filter_tasks, sort_tasks},
+    config::{Config, DateFilter, ListSort, Order, SortKey},
+    diff::PlanDiff,
+    export::{ExportFormat, export},
+    flow::{self, FlowFormat},
+    history,
+    paths::Paths,
+    query::Query,
+    report, serve,
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "View Microsoft Planner exports in the terminal")]
+pub struct Cli {
+    /// Planner export to open, defaults to $XDG_DATA_HOME/ms_planner_viewer/plan.xlsx
+    #[arg(long, global = true)]
+    pub plan: Option<PathBuf>,
+    /// Config file, defaults to $XDG_CONFIG_HOME/ms_planner_viewer/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Load a preset saved in the config as the active filter and sort
+    #[arg(long, global = true)]
+    pub preset: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the tasks matching the filter as a table
+    List(Box<ListArgs>),
+    /// Write a self-contained HTML report of the tasks matching the filter,
+    /// taking the filter arguments of list
+    Report(Box<ListArgs>),
+    /// Print every field of a single task
+    Show { id: String },
+    /// Write the burndown and cumulative flow of the archived exports,
+    /// counting the tasks matching the saved filter
+    Flow {
+        /// csv or svg
+        #[arg(long, default_value = "csv")]
+        format: FlowFormat,
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print what changed since an earlier export in the history
+    Diff {
+        /// Export date of the earlier export, defaults to the previous one
+        #[arg(long)]
+        from: Option<NaiveDate>,
+    },
+    /// Serve the plan as JSON over HTTP, reloading it when the watcher moves
+    /// in a new export: GET /plan, /tasks?query=...&saved, /tasks/{id} and
+    /// /stats?query=...
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ListArgs {
+    /// Start from the filter and sort saved in the config, implied by --preset
+    #[arg(long)]
+    pub saved: bool,
+    #[arg(long)]
+    pub bucket: Vec<String>,
+    #[arg(long)]
+    pub progress: Vec<Progress>,
+    #[arg(long)]
+    pub priority: Vec<Priority>,
+    /// Task must have at least one of the labels
+    #[arg(long)]
+    pub label: Vec<String>,
+    /// Task must be assigned to at least one of the people
+    #[arg(long)]
+    pub assigned: Vec<String>,
+    /// Case insensitive substring of the task name
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Date filter on the deadline, e.g. "next 14 days", "overdue" (unfinished only) or "2025-01-01..2025-01-31"
+    #[arg(long)]
+    pub deadline: Option<DateFilter>,
+    #[arg(long)]
+    pub start: Option<DateFilter>,
+    #[arg(long)]
+    pub created: Option<DateFilter>,
+    #[arg(long)]
+    pub completed: Option<DateFilter>,
+    /// Query in the filter language, e.g. 'label:bug or priority>=Important'
+    #[arg(long, short)]
+    pub query: Option<Query>,
+    /// Sort keys in order of precedence, e.g. "deadline:asc,priority,labels:count".
+    /// Labels and assignees sort by their first entry, "count" or "rank"
+    #[arg(long, value_delimiter = ',')]
+    pub sort: Vec<SortArg>,
+    /// Order of the sort keys that don't name one
+    #[arg(long)]
+    pub order: Option<Order>,
+    /// Print as csv, json, markdown, ics (to-dos) or ics-events instead of a table
+    #[arg(long)]
+    pub export: Option<ExportFormat>,
+    /// Columns to export, defaults to the ones in the config
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<Column>,
+    /// Write to a file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl ListArgs {
+    /// The config with the filter and sort of the arguments applied.
+    pub fn to_config(&self, config: &Config) -> AnyResult<Config> {
+        let mut config = if self.saved {
+            config.clone()
+        } else {
+            Config {
+                filter: Default::default(),
+                sort: Default::default(),
+                ..config.clone()
+            }
+        };
+        let filter = &mut config.filter;
+        filter.bucket.or.extend(self.bucket.iter().cloned());
+        filter.progress.or.extend(self.progress.iter().cloned());
+        filter.priority.or.extend(self.priority.iter().cloned());
+        filter.labels.or.extend(self.label.iter().cloned());
+        filter.assigned_to.or.extend(self.assigned.iter().cloned());
+        if let Some(name) = &self.name {
+            filter.name = name.clone();
+        }
+        for (date_filter, arg) in [
+            (&mut filter.deadline, &self.deadline),
+            (&mut filter.start_date, &self.start),
+            (&mut filter.create_date, &self.created),
+            (&mut filter.complete_date, &self.completed),
+        ] {
+            if let Some(arg) = arg {
+                *date_filter = arg.clone();
+            }
+        }
+        if let Some(query) = &self.query {
+            filter.query = Some(query.clone());
+        }
+        if !self.sort.is_empty() {
+            config.sort.keys.clear();
+        }
+        for arg in &self.sort {
+            config.sort.keys.push(SortKey {
+                column: arg.column,
+                order: arg.order.or(self.order).unwrap_or_default(),
+                by: arg.by,
+            });
+        }
+        if let (Some(order), true) = (self.order, self.sort.is_empty()) {
+            config.sort.keys.iter_mut().for_each(|k| k.order = order);
+        }
+        Ok(config)
+    }
+}
+
+/// A sort key as `column`, optionally followed by `:order` and `:list sort`.
+#[derive(Debug, Clone)]
+pub struct SortArg {
+    pub column: Column,
+    pub order: Option<Order>,
+    pub by: ListSort,
+}
+impl FromStr for SortArg {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let mut arg = Self {
+            column: parts.next().unwrap_or_default().trim().parse()?,
+            order: None,
+            by: ListSort::default(),
+        };
+        for part in parts {
+            match part.parse() {
+                Ok(order) => arg.order = Some(order),
+                Err(_) => arg.by = part.parse()?,
+            }
+        }
+        Ok(arg)
+    }
+}
+
+pub fn run(command: Command, plan: &Plan, config: &Config, paths: &Paths) -> AnyResult<()> {
+    let mut out = std::io::stdout().lock();
+    match command {
+        Command::List(args) => {
+            let config = args.to_config(config)?;
+            let mut tasks = filter_tasks(&config, &plan.tasks);
+            sort_tasks(&config, &mut tasks);
+            let mut out = output_to(&args.output, out)?;
+            match args.export {
+                Some(format) => {
+                    let columns = if args.columns.is_empty() {
+                        &config.export.columns
+                    } else {
+                        &args.columns
+                    };
+                    export(&mut out, &tasks, columns, format, &plan.id)?;
+                }
+                None => write_table(&mut out, &tasks)?,
+            }
+            out.flush()?;
+        }
+        Command::Report(args) => {
+            if args.export.is_some() || !args.columns.is_empty() {
+                anyhow::bail!("report takes no --export or --columns");
+            }
+            let config = args.to_config(config)?;
+            let mut tasks = filter_tasks(&config, &plan.tasks);
+            sort_tasks(&config, &mut tasks);
+            let mut out = output_to(&args.output, out)?;
+            let today = chrono::Local::now().date_naive();
+            report::write_report(&mut out, plan, &tasks, today)?;
+            out.flush()?;
+        }
+        Command::Show { id } => {
+            let task = plan
+                .tasks
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow::anyhow!("no task with id {id}"))?;
+            write_task(&mut out, task, &plan.id)?;
+        }
+        Command::Flow { format, output } => {
+            let points = flow::series(&paths.history()?, &plan.id, config)?;
+            let mut out = output_to(&output, out)?;
+            flow::export(&mut out, &points, format)?;
+            out.flush()?;
+        }
+        Command::Diff { from } => {
+            let snapshot = match from {
+                Some(from) => history::snapshots(&paths.history()?, &plan.id)?
+                    .into_iter()
+                    .find(|s| s.export_date == from),
+                None => history::previous(&paths.history()?, plan)?,
+            };
+            let snapshot =
+                snapshot.ok_or_else(|| anyhow::anyhow!("no such export in the history"))?;
+            write!(out, "{}", PlanDiff::new(&snapshot.load()?, plan))?;
+        }
+        Command::Serve { addr } => serve::serve(&addr, plan.clone(), config, paths)?,
+    }
+    Ok(())
+}
+
+/// The file at `path`, or `stdout` without one.
+fn output_to(
+    path: &Option<PathBuf>,
+    stdout: impl Write + 'static,
+) -> std::io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(stdout),
+    })
+}
+
+const TABLE_HEADERS: [&str; 8] = [
+    "Id", "Name", "Bucket", "Progress", "Priority", "Items", "Created", "Deadline",
+];
+
+fn task_to_cells(task: &Task) -> [String; TABLE_HEADERS.len()] {
+    [
+        task.id.clone(),
+        task.name.clone(),
+        task.bucket.clone(),
+        task.progress.to_string(),
+        task.priority.to_string(),
+        task.items_completed
+            .map(|(done, all)| format!("{done}/{all}"))
+            .unwrap_or_default(),
+        task.create_date.to_string(),
+        task.deadline.map(|d| d.to_string()).unwrap_or_default(),
+    ]
+}
+
+pub fn write_table(out: &mut impl Write, tasks: &[Task]) -> std::io::Result<()> {
+    let rows: Vec<_> = tasks.iter().map(task_to_cells).collect();
+    let mut widths = TABLE_HEADERS.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let headers = TABLE_HEADERS.map(str::to_string);
+    for row in std::iter::once(&headers).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn write_task(out: &mut impl Write, task: &Task, plan_id: &str) -> std::io::Result<()> {
+    let optional_date = |d: Option<chrono::NaiveDate>| d.map(|d| d.to_string()).unwrap_or_default();
+    writeln!(out, "id:            {}", task.id)?;
+    writeln!(out, "name:          {}", task.name)?;
+    writeln!(out, "bucket:        {}", task.bucket)?;
+    writeln!(out, "progress:      {}", task.progress)?;
+    writeln!(out, "priority:      {}", task.priority)?;
+    writeln!(out, "assigned to:   {}", task.assigned_to.join(", "))?;
+    writeln!(out, "labels:        {}", task.labels.join(", "))?;
+    writeln!(out, "created by:    {}", task.created_by)?;
+    writeln!(out, "created:       {}", task.create_date)?;
+    writeln!(out, "start:         {}", optional_date(task.start_date))?;
+    writeln!(out, "deadline:      {}", optional_date(task.deadline))?;
+    writeln!(out, "completed:     {}", optional_date(task.complete_date))?;
+    writeln!(out, "completed by:  {}", task.completed_by)?;
+    writeln!(out, "late:          {}", task.late)?;
+    writeln!(out, "url:           {}", task.to_url(plan_id))?;
+    if let Some((done, all)) = task.items_completed {
+        writeln!(out, "items:         {done}/{all}")?;
+    }
+    for item in &task.items {
+        writeln!(out, "  - {item}")?;
+    }
+    for (name, value) in &task.extra {
+        writeln!(out, "{name}: {value}")?;
+    }
+    if !task.description.is_empty() {
+        writeln!(out, "\n{}", task.description)?;
+    }
+    Ok(())
+}
@@ -1,14 +1,128 @@
 use std::path::Path;
 
-use crate::{AnyResult, Column, Priority, Progress};
+use crate::{
+    AnyResult, Column, Priority, Progress, SimpleError, Task, export::ExportFormat, group::GroupBy,
+    query::Query,
+};
+use chrono::{Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Config {
     pub filter: TaskFilter,
     pub sort: TaskSort,
+    /// Export to move over the plan when it shows up, or a glob like
+    /// `~/Downloads/*.xlsx` to take the newest export of the same plan from.
     pub scan_path: Option<std::path::PathBuf>,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>,
+    #[serde(default)]
+    pub table: TableConfig,
+    /// Splits the task table into collapsible sections.
+    #[serde(default)]
+    pub group_by: Option<GroupBy>,
+}
+
+/// A named filter and sort that can be loaded over the active ones.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Preset {
+    pub filter: TaskFilter,
+    pub sort: TaskSort,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ExportConfig {
+    pub format: ExportFormat,
+    pub columns: Vec<Column>,
+    /// File the TUI exports to, `plan_export.<ext>` when unset.
+    pub path: Option<std::path::PathBuf>,
+}
+impl Default for ExportConfig {
+    fn default() -> Self {
+        use Column as C;
+        Self {
+            format: ExportFormat::default(),
+            columns: vec![C::Name, C::Bucket, C::Progress, C::Priority, C::Deadline],
+            path: None,
+        }
+    }
+}
+impl ExportConfig {
+    pub fn path(&self) -> std::path::PathBuf {
+        self.path_for(self.format)
+    }
+    /// The export file with the extension of `format` when it isn't the
+    /// configured one.
+    pub fn path_for(&self, format: ExportFormat) -> std::path::PathBuf {
+        match &self.path {
+            Some(path) if format == self.format => path.clone(),
+            Some(path) => path.with_extension(format.extension()),
+            None => std::path::PathBuf::from(format!("plan_export.{}", format.extension())),
+        }
+    }
+}
+/// Columns of the task table, from left to right.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TableConfig {
+    pub columns: Vec<TableColumn>,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct TableColumn {
+    pub column: Column,
+    /// Width in cells, columns without one share the remaining space.
+    #[serde(default)]
+    pub width: Option<u16>,
+}
+impl TableColumn {
+    fn new(column: Column) -> Self {
+        use Column as C;
+        let width = match column {
+            C::Name | C::Description => None,
+            C::Progress | C::Priority => Some(3),
+            C::Late => Some(4),
+            C::Items => Some(5),
+            C::CreateDate | C::StartDate | C::Deadline | C::CompleteDate => Some(10),
+            C::Bucket | C::CreatedBy => Some(15),
+            C::Labels | C::AssignedTo => Some(20),
+        };
+        Self { column, width }
+    }
+}
+impl Default for TableConfig {
+    fn default() -> Self {
+        use Column as C;
+        let columns = [
+            C::Name,
+            C::Bucket,
+            C::Progress,
+            C::Priority,
+            C::Items,
+            C::CreateDate,
+            C::Deadline,
+        ];
+        Self {
+            columns: columns.into_iter().map(TableColumn::new).collect(),
+        }
+    }
+}
+impl TableConfig {
+    pub fn is_visible(&self, column: Column) -> bool {
+        self.columns.iter().any(|c| c.column == column)
+    }
+    /// Hides a visible column, or shows a hidden one as the last column.
+    pub fn toggle(&mut self, column: Column) {
+        if self.is_visible(column) {
+            self.columns.retain(|c| c.column != column);
+        } else {
+            self.columns.push(TableColumn::new(column));
+        }
+    }
 }
 impl Config {
     pub fn from_file(path: impl AsRef<Path>) -> AnyResult<Self> {
@@ -16,6 +130,56 @@ impl Config {
         let config: Config = toml::from_str(&s)?;
         Ok(config)
     }
+    /// Reads the config, or the default one when there is none.
+    pub fn load(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let path = path.as_ref();
+        if std::fs::exists(path)? {
+            Self::from_file(path)
+        } else {
+            Ok(Config::default())
+        }
+    }
+    /// Reads the config, writing the default one first when there is none.
+    pub fn load_or_create(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let path = path.as_ref();
+        if std::fs::exists(path)? {
+            Self::from_file(path)
+        } else {
+            let config = Config::default();
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            config.to_file(path)?;
+            Ok(config)
+        }
+    }
+    pub fn apply_preset(&mut self, name: &str) -> Result<(), SimpleError> {
+        let preset = self
+            .presets
+            .get(name)
+            .ok_or_else(|| format!("no preset named {name:?}"))?;
+        self.filter = preset.filter.clone();
+        self.sort = preset.sort.clone();
+        Ok(())
+    }
+    pub fn save_preset(&mut self, name: &str) {
+        let preset = Preset {
+            filter: self.filter.clone(),
+            sort: self.sort.clone(),
+        };
+        self.presets.insert(name.to_string(), preset);
+    }
+    pub fn rename_preset(&mut self, old: &str, new: &str) -> Result<(), SimpleError> {
+        if old != new && self.presets.contains_key(new) {
+            return Err(format!("preset {new:?} already exists").into());
+        }
+        let preset = self
+            .presets
+            .remove(old)
+            .ok_or_else(|| format!("no preset named {old:?}"))?;
+        self.presets.insert(new.to_string(), preset);
+        Ok(())
+    }
     pub fn to_file(&self, path: impl AsRef<Path>) -> AnyResult<()> {
         let stdout = toml::to_string_pretty(self)?;
         std::fs::write(path, &stdout)?;
@@ -28,6 +192,18 @@ pub struct UniqueTaskKeys {
     pub labels: Vec<String>,
     pub people: Vec<String>,
 }
+impl UniqueTaskKeys {
+    pub fn new(tasks: &[Task]) -> Self {
+        let buckets = tasks.iter().map(|t| &t.bucket);
+        let labels = tasks.iter().flat_map(|t| &t.labels);
+        let people = tasks.iter().flat_map(|t| &t.assigned_to);
+        Self {
+            buckets: get_unique_strings(buckets),
+            labels: get_unique_strings(labels),
+            people: get_unique_strings(people),
+        }
+    }
+}
 
 pub fn get_unique_strings<'a, I>(i: I) -> Vec<String>
 where
@@ -41,6 +217,7 @@ where
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct TaskFilter {
     pub ids: Vec<String>,
     pub filter_ids: bool,
@@ -52,6 +229,11 @@ pub struct TaskFilter {
     pub assigned_to: MultiTagFilter,
     pub created_by: TagFilter<String>,
     pub description: String,
+    pub create_date: DateFilter,
+    pub start_date: DateFilter,
+    pub deadline: DateFilter,
+    pub complete_date: DateFilter,
+    pub query: Option<Query>,
 }
 impl TaskFilter {
     pub fn reset_filter(&mut self, column: Column) {
@@ -64,13 +246,161 @@ impl TaskFilter {
             C::Priority => self.priority = TagFilter::default(),
             C::Description => self.description = String::new(),
             C::Name => self.name = String::new(),
-            C::StartDate | C::Deadline | C::CreateDate => (),
-            C::CompleteDate => (),
+            C::StartDate => self.start_date = DateFilter::default(),
+            C::Deadline => self.deadline = DateFilter::default(),
+            C::CreateDate => self.create_date = DateFilter::default(),
+            C::CompleteDate => self.complete_date = DateFilter::default(),
+            C::CreatedBy | C::Late | C::Items => (),
         };
     }
+    pub fn date_filter_mut(&mut self, column: Column) -> Option<&mut DateFilter> {
+        use Column as C;
+        match column {
+            C::StartDate => Some(&mut self.start_date),
+            C::Deadline => Some(&mut self.deadline),
+            C::CreateDate => Some(&mut self.create_date),
+            C::CompleteDate => Some(&mut self.complete_date),
+            _ => None,
+        }
+    }
+}
+
+/// Filter on one of the date columns, stored in the config as the text it is
+/// parsed from, e.g. `"next 7 days"` or `"2025-01-01..2025-01-31"`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum DateFilter {
+    #[default]
+    Any,
+    HasDate,
+    NoDate,
+    /// Inclusive on both ends, an open end is unbounded.
+    Between(Option<NaiveDate>, Option<NaiveDate>),
+    /// From today up to and including today + n days.
+    Next(u32),
+    /// From today - n days up to and including today.
+    Last(u32),
+    ThisWeek,
+    LastWeek,
+    NextWeek,
+    /// Before today. On deadlines it leaves out done tasks, see
+    /// `filter_deadline`.
+    Overdue,
+}
+impl DateFilter {
+    pub const SYNTAX: &str = "any | has | none | overdue (unfinished) | this/last/next week | next/last <n> days | <from>..<to> (YYYY-MM-DD)";
+    pub fn filter(&self, date: Option<NaiveDate>) -> bool {
+        self.filter_at(date, chrono::Local::now().date_naive())
+    }
+    pub fn filter_at(&self, date: Option<NaiveDate>, today: NaiveDate) -> bool {
+        let Some(date) = date else {
+            return matches!(self, Self::Any | Self::NoDate);
+        };
+        let week_start = today.week(chrono::Weekday::Mon).first_day();
+        let in_week = |offset: i64| {
+            let start = week_start + Duration::weeks(offset);
+            start <= date && date < start + Duration::weeks(1)
+        };
+        match self {
+            Self::Any | Self::HasDate => true,
+            Self::NoDate => false,
+            Self::Between(from, to) => {
+                from.is_none_or(|from| from <= date) && to.is_none_or(|to| date <= to)
+            }
+            // an end past the last representable date is unbounded
+            Self::Next(days) => {
+                let end = today.checked_add_signed(Duration::days(*days as i64));
+                today <= date && end.is_none_or(|end| date <= end)
+            }
+            Self::Last(days) => {
+                let start = today.checked_sub_signed(Duration::days(*days as i64));
+                start.is_none_or(|start| start <= date) && date <= today
+            }
+            Self::ThisWeek => in_week(0),
+            Self::LastWeek => in_week(-1),
+            Self::NextWeek => in_week(1),
+            Self::Overdue => date < today,
+        }
+    }
+    /// Filters on the deadline of the task, where overdue means
+    /// `Task::is_overdue`, so done tasks are never overdue.
+    pub fn filter_deadline(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::Overdue => task.is_overdue(today),
+            _ => self.filter_at(task.deadline, today),
+        }
+    }
+    pub fn has_filter(&self) -> bool {
+        *self != Self::Any
+    }
+}
+impl FromStr for DateFilter {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let date = |s: &str| -> Result<Option<NaiveDate>, SimpleError> {
+            match s.trim() {
+                "" => Ok(None),
+                s => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map(Some)
+                    .map_err(|_| format!("not a valid date: {s}").into()),
+            }
+        };
+        match words.as_slice() {
+            [] | ["any"] => Ok(Self::Any),
+            ["has"] | ["has", "date"] => Ok(Self::HasDate),
+            ["none"] | ["no", "date"] => Ok(Self::NoDate),
+            ["overdue"] => Ok(Self::Overdue),
+            ["this", "week"] => Ok(Self::ThisWeek),
+            ["last", "week"] => Ok(Self::LastWeek),
+            ["next", "week"] => Ok(Self::NextWeek),
+            ["next", n] | ["next", n, "days"] => n
+                .parse()
+                .map(Self::Next)
+                .map_err(|_| format!("not a number of days: {n}").into()),
+            ["last", n] | ["last", n, "days"] => n
+                .parse()
+                .map(Self::Last)
+                .map_err(|_| format!("not a number of days: {n}").into()),
+            _ => match s.split_once("..") {
+                Some((from, to)) => Ok(Self::Between(date(from)?, date(to)?)),
+                None => Err(format!("not a valid date filter: {s}").into()),
+            },
+        }
+    }
+}
+impl Display for DateFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let date = |d: &Option<NaiveDate>| d.map(|d| d.to_string()).unwrap_or_default();
+        match self {
+            Self::Any => write!(f, "any"),
+            Self::HasDate => write!(f, "has date"),
+            Self::NoDate => write!(f, "no date"),
+            Self::Between(from, to) => write!(f, "{}..{}", date(from), date(to)),
+            Self::Next(days) => write!(f, "next {days} days"),
+            Self::Last(days) => write!(f, "last {days} days"),
+            Self::ThisWeek => write!(f, "this week"),
+            Self::LastWeek => write!(f, "last week"),
+            Self::NextWeek => write!(f, "next week"),
+            Self::Overdue => write!(f, "overdue"),
+        }
+    }
+}
+impl TryFrom<String> for DateFilter {
+    type Error = SimpleError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl From<DateFilter> for String {
+    fn from(value: DateFilter) -> Self {
+        value.to_string()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct MultiTagFilter {
     pub and: Vec<String>,
     pub or: Vec<String>,
@@ -78,22 +408,171 @@ pub struct MultiTagFilter {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct TagFilter<T> {
     pub or: Vec<T>,
     pub not: Vec<T>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+/// Sort keys in order of precedence, later keys break ties of earlier ones.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(from = "TaskSortRepr")]
 pub struct TaskSort {
+    pub keys: Vec<SortKey>,
+    /// Labels in the order `ListSort::Ranking` sorts them, unlisted ones last.
+    pub label_ranking: Vec<String>,
+    /// People in the order `ListSort::Ranking` sorts them, unlisted ones last.
+    pub people_ranking: Vec<String>,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct SortKey {
     pub column: Column,
     pub order: Order,
+    /// How labels and assignees compare, ignored for the other columns.
+    #[serde(default)]
+    pub by: ListSort,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// Alphabetically by the first entry, tasks without any first.
+    #[default]
+    First,
+    Count,
+    /// By the best ranked entry in the configured ranking.
+    Ranking,
+}
+impl ListSort {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::First => Self::Count,
+            Self::Count => Self::Ranking,
+            Self::Ranking => Self::First,
+        }
+    }
+}
+impl FromStr for ListSort {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "first" => Ok(Self::First),
+            "count" => Ok(Self::Count),
+            "rank" | "ranking" => Ok(Self::Ranking),
+            _ => Err(format!("not a valid list sort: {s}").into()),
+        }
+    }
 }
-#[derive(Deserialize, Serialize, Debug, Clone, Default, Copy)]
+/// Also reads the single `column` and `order` of older configs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TaskSortRepr {
+    Keys {
+        keys: Vec<SortKey>,
+        #[serde(default)]
+        label_ranking: Vec<String>,
+        #[serde(default)]
+        people_ranking: Vec<String>,
+    },
+    Single(SortKey),
+}
+impl From<TaskSortRepr> for TaskSort {
+    fn from(value: TaskSortRepr) -> Self {
+        match value {
+            TaskSortRepr::Keys {
+                keys,
+                label_ranking,
+                people_ranking,
+            } => Self {
+                keys,
+                label_ranking,
+                people_ranking,
+            },
+            TaskSortRepr::Single(key) => Self {
+                keys: vec![key],
+                ..Default::default()
+            },
+        }
+    }
+}
+impl Default for TaskSort {
+    fn default() -> Self {
+        Self {
+            keys: vec![SortKey {
+                column: Column::default(),
+                order: Order::default(),
+                by: ListSort::default(),
+            }],
+            label_ranking: Vec::new(),
+            people_ranking: Vec::new(),
+        }
+    }
+}
+impl TaskSort {
+    pub fn position(&self, column: Column) -> Option<usize> {
+        self.keys.iter().position(|k| k.column == column)
+    }
+    /// Makes the column the only key, keeping the order of the old primary key.
+    pub fn set_primary(&mut self, column: Column) {
+        let order = self.keys.first().map(|k| k.order).unwrap_or_default();
+        let by = ListSort::default();
+        self.keys = vec![SortKey { column, order, by }];
+    }
+    /// Adds the column as the last tie-breaker.
+    pub fn add(&mut self, column: Column) {
+        if self.position(column).is_none() {
+            let (order, by) = Default::default();
+            self.keys.push(SortKey { column, order, by });
+        }
+    }
+    pub fn remove(&mut self, column: Column) {
+        self.keys.retain(|k| k.column != column);
+    }
+    pub fn toggle_order(&mut self, column: Column) {
+        if let Some(i) = self.position(column) {
+            self.keys[i].order = self.keys[i].order.toggle();
+        }
+    }
+    pub fn cycle_list_sort(&mut self, column: Column) {
+        if let Some(i) = self.position(column) {
+            self.keys[i].by = self.keys[i].by.next();
+        }
+    }
+    /// Moves the key of the column one place towards the front.
+    pub fn promote(&mut self, column: Column) {
+        if let Some(i) = self.position(column).filter(|&i| i > 0) {
+            self.keys.swap(i, i - 1);
+        }
+    }
+    /// Moves the key of the column one place towards the back.
+    pub fn demote(&mut self, column: Column) {
+        if let Some(i) = self.position(column).filter(|&i| i + 1 < self.keys.len()) {
+            self.keys.swap(i, i + 1);
+        }
+    }
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Default, Copy, PartialEq, Eq)]
 pub enum Order {
     Asc,
     #[default]
     Desc,
 }
+impl Order {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+impl FromStr for Order {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "asc" => Ok(Self::Asc),
+            "desc" => Ok(Self::Desc),
+            _ => Err(format!("not a valid order: {s}").into()),
+        }
+    }
+}
 impl<T: PartialEq> TagFilter<T> {
     pub fn filter(&self, tag: &T) -> bool {
         if !self.not.is_empty() && self.not.contains(tag) {
@@ -132,3 +611,30 @@ pub fn no_case_contains(pattern: &str, text: &str) -> bool {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_filter_large_day_counts() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let next: DateFilter = "next 4000000000 days".parse().unwrap();
+        assert!(next.filter_at(Some(NaiveDate::MAX), today));
+        assert!(!next.filter_at(NaiveDate::from_ymd_opt(2026, 10, 16), today));
+        let last: DateFilter = "last 4000000000 days".parse().unwrap();
+        assert!(last.filter_at(Some(NaiveDate::MIN), today));
+        assert!(!last.filter_at(NaiveDate::from_ymd_opt(2026, 10, 18), today));
+    }
+
+    #[test]
+    fn date_filter_day_counts() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let next: DateFilter = "next 3 days".parse().unwrap();
+        assert!(next.filter_at(NaiveDate::from_ymd_opt(2026, 10, 20), today));
+        assert!(!next.filter_at(NaiveDate::from_ymd_opt(2026, 10, 21), today));
+        let last: DateFilter = "last 2".parse().unwrap();
+        assert!(last.filter_at(NaiveDate::from_ymd_opt(2026, 10, 15), today));
+        assert!(!last.filter_at(NaiveDate::from_ymd_opt(2026, 10, 14), today));
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{Duration, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Gauge, Sparkline},
+};
+use serde::Serialize;
+
+use crate::{Priority, Progress, Task, app::App, config, group};
+
+/// Weeks shown in the completed and created per week charts.
+pub const WEEKS: usize = 26;
+
+#[derive(Serialize)]
+pub struct Stats {
+    /// Bucket names with the count of tasks per `Progress::items`.
+    pub buckets: Vec<(String, [u64; 3])>,
+    /// Count of tasks per `Priority::items`.
+    pub priorities: [u64; 4],
+    pub open: u64,
+    pub overdue: u64,
+    /// Per week, the oldest first and this week last.
+    pub completed_per_week: [u64; WEEKS],
+    pub created_per_week: [u64; WEEKS],
+}
+impl Stats {
+    pub fn new(tasks: &[Task], today: NaiveDate) -> Self {
+        let mut buckets = config::get_unique_strings(tasks.iter().map(|t| &t.bucket));
+        buckets.sort();
+        let progresses = Progress::items();
+        let buckets = buckets
+            .into_iter()
+            .map(|bucket| {
+                let mut counts = [0; 3];
+                for task in tasks.iter().filter(|t| t.bucket == bucket) {
+                    if let Some(i) = progresses.iter().position(|p| *p == task.progress) {
+                        counts[i] += 1;
+                    }
+                }
+                (bucket, counts)
+            })
+            .collect();
+        let mut priorities = [0; 4];
+        for task in tasks {
+            if let Some(i) = Priority::items().iter().position(|p| *p == task.priority) {
+                priorities[i] += 1;
+            }
+        }
+        let open: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.progress != Progress::Done)
+            .collect();
+        let overdue = open.iter().filter(|t| t.is_overdue(today)).count();
+        let this_week = group::week_start(today);
+        let per_week = |dates: &mut dyn Iterator<Item = NaiveDate>| {
+            let mut weeks = [0; WEEKS];
+            for date in dates {
+                let ago = (this_week - group::week_start(date)).num_weeks();
+                if (0..WEEKS as i64).contains(&ago) {
+                    weeks[WEEKS - 1 - ago as usize] += 1;
+                }
+            }
+            weeks
+        };
+        Self {
+            buckets,
+            priorities,
+            open: open.len() as u64,
+            overdue: overdue as u64,
+            completed_per_week: per_week(&mut tasks.iter().filter_map(|t| t.complete_date)),
+            created_per_week: per_week(&mut tasks.iter().map(|t| t.create_date)),
+        }
+    }
+}
+
+fn progress_color(progress: &Progress) -> Color {
+    match progress {
+        Progress::NotStarted => Color::Gray,
+        Progress::Ongoing => Color::Blue,
+        Progress::Done => Color::Green,
+    }
+}
+
+fn week_chart<'a>(title: &'a str, weeks: &'a [u64], today: NaiveDate) -> Sparkline<'a> {
+    let first = group::week_start(today) - Duration::weeks(WEEKS as i64 - 1);
+    let total: u64 = weeks.iter().sum();
+    let block = Block::bordered()
+        .title(format!("{title} per week since {first}"))
+        .title_bottom(format!("total {total}, this week {}", weeks[WEEKS - 1]));
+    Sparkline::default().data(weeks).block(block)
+}
+
+pub fn render_dashboard(app: &mut App, f: &mut Frame, area: Rect) {
+    let today = chrono::Local::now().date_naive();
+    let stats = Stats::new(&app.displayed_tasks, today);
+    let block = Block::bordered().title(format!(
+        "dashboard of {} filtered tasks (esc: back)",
+        app.displayed_tasks.len()
+    ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [bars, gauge, weeks] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(3),
+        Constraint::Length(8),
+    ])
+    .areas(inner);
+    let [buckets_area, priority_area] =
+        Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(bars);
+
+    let legend: Vec<_> = Progress::items()
+        .iter()
+        .map(|p| format!("■ {p}").fg(progress_color(p)))
+        .collect();
+    let mut buckets = BarChart::default()
+        .block(
+            Block::bordered()
+                .title("tasks per bucket and progress")
+                .title_bottom(Line::from(
+                    legend
+                        .into_iter()
+                        .flat_map(|s| [s, " ".into()])
+                        .collect::<Vec<_>>(),
+                )),
+        )
+        .bar_width(3)
+        .bar_gap(0)
+        .group_gap(2);
+    for (bucket, counts) in &stats.buckets {
+        let bars: Vec<Bar> = Progress::items()
+            .iter()
+            .zip(counts)
+            .map(|(p, &n)| Bar::default().value(n).style(progress_color(p)))
+            .collect();
+        buckets = buckets.data(
+            BarGroup::default()
+                .label(Line::from(bucket.clone()))
+                .bars(&bars),
+        );
+    }
+    f.render_widget(buckets, buckets_area);
+
+    let bars: Vec<Bar> = Priority::items()
+        .iter()
+        .zip(stats.priorities)
+        .map(|(p, n)| Bar::default().value(n).label(Line::from(p.to_string())))
+        .collect();
+    let priorities = BarChart::default()
+        .block(Block::bordered().title("priority"))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(
+            (priority_area.width.saturating_sub(2) / 4)
+                .saturating_sub(1)
+                .max(1),
+        )
+        .bar_style(Style::new().yellow());
+    f.render_widget(priorities, priority_area);
+
+    let ratio = match stats.open {
+        0 => 0.0,
+        open => stats.overdue as f64 / open as f64,
+    };
+    let overdue = Gauge::default()
+        .block(Block::bordered().title("overdue of the open tasks"))
+        .gauge_style(Style::new().light_red())
+        .ratio(ratio)
+        .label(format!(
+            "{}/{} ({:.0}%)",
+            stats.overdue,
+            stats.open,
+            ratio * 100.0
+        ));
+    f.render_widget(overdue, gauge);
+
+    let [completed, created] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(weeks);
+    let chart = week_chart("completed", &stats.completed_per_week, today).green();
+    f.render_widget(chart, completed);
+    let chart = week_chart("created", &stats.created_per_week, today).blue();
+    f.render_widget(chart, created);
+}
@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+
+use crate::{Plan, Priority, Progress, Task};
+
+/// What changed between two exports of the same plan.
+#[derive(Debug, Clone, Default)]
+pub struct PlanDiff {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub added: Vec<Task>,
+    pub removed: Vec<Task>,
+    pub changed: Vec<(Task, Vec<Change>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Bucket(String, String),
+    Progress(Progress, Progress),
+    Priority(Priority, Priority),
+    Deadline(Option<NaiveDate>, Option<NaiveDate>),
+    Assigned {
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+}
+
+impl PlanDiff {
+    pub fn new(old: &Plan, new: &Plan) -> Self {
+        let find = |tasks: &[Task], id: &str| tasks.iter().find(|t| t.id == id).cloned();
+        let added = new
+            .tasks
+            .iter()
+            .filter(|t| find(&old.tasks, &t.id).is_none())
+            .cloned()
+            .collect();
+        let removed = old
+            .tasks
+            .iter()
+            .filter(|t| find(&new.tasks, &t.id).is_none())
+            .cloned()
+            .collect();
+        let changed = new
+            .tasks
+            .iter()
+            .filter_map(|t| {
+                let changes = Change::between(&find(&old.tasks, &t.id)?, t);
+                (!changes.is_empty()).then(|| (t.clone(), changes))
+            })
+            .collect();
+        Self {
+            from: old.export_date,
+            to: new.export_date,
+            added,
+            removed,
+            changed,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Change {
+    pub fn between(old: &Task, new: &Task) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if old.bucket != new.bucket {
+            changes.push(Change::Bucket(old.bucket.clone(), new.bucket.clone()));
+        }
+        if old.progress != new.progress {
+            changes.push(Change::Progress(old.progress.clone(), new.progress.clone()));
+        }
+        if old.priority != new.priority {
+            changes.push(Change::Priority(old.priority.clone(), new.priority.clone()));
+        }
+        if old.deadline != new.deadline {
+            changes.push(Change::Deadline(old.deadline, new.deadline));
+        }
+        let added: Vec<String> = new
+            .assigned_to
+            .iter()
+            .filter(|p| !old.assigned_to.contains(p))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = old
+            .assigned_to
+            .iter()
+            .filter(|p| !new.assigned_to.contains(p))
+            .cloned()
+            .collect();
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(Change::Assigned { added, removed });
+        }
+        changes
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let date = |d: &Option<NaiveDate>| d.map_or("none".to_string(), |d| d.to_string());
+        match self {
+            Self::Bucket(old, new) => write!(f, "moved {old} -> {new}"),
+            Self::Progress(old, new) => write!(f, "progress {old} -> {new}"),
+            Self::Priority(old, new) => write!(f, "priority {old} -> {new}"),
+            Self::Deadline(old, new) => write!(f, "deadline {} -> {}", date(old), date(new)),
+            Self::Assigned { added, removed } => {
+                write!(f, "reassigned")?;
+                if !added.is_empty() {
+                    write!(f, " +{}", added.join(", +"))?;
+                }
+                if !removed.is_empty() {
+                    write!(f, " -{}", removed.join(", -"))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for PlanDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "changes from {} to {}", self.from, self.to)?;
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        if !self.added.is_empty() {
+            writeln!(f, "\nadded ({}):", self.added.len())?;
+            for task in &self.added {
+                writeln!(f, "  + {} [{}]", task.name, task.bucket)?;
+            }
+        }
+        if !self.removed.is_empty() {
+            writeln!(f, "\nremoved ({}):", self.removed.len())?;
+            for task in &self.removed {
+                writeln!(f, "  - {} [{}]", task.name, task.bucket)?;
+            }
+        }
+        if !self.changed.is_empty() {
+            writeln!(f, "\nchanged ({}):", self.changed.len())?;
+            for (task, changes) in &self.changed {
+                writeln!(f, "  ~ {}", task.name)?;
+                for change in changes {
+                    writeln!(f, "      {change}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
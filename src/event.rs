@@ -1,39 +1,153 @@
 use std::{
-    sync::mpsc::{Receiver, channel},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError, Sender, channel},
+    },
     time::Duration,
 };
 
+use notify::{RecursiveMode, Watcher};
 use ratatui::crossterm::event::{self, Event};
 
 use crate::{
-    PLAN_PATH,
     config::Config,
-    scan_dir::{self, move_file},
+    paths::Paths,
+    scan_dir::{ScanPath, Scanner},
 };
 
+/// How often the scan path is checked when the file system can't be watched.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Checks the scan path this often even while watching, to catch events that
+/// were missed or arrived while paused.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
 pub enum MsEvent {
     Crossterm(std::io::Result<Event>),
     PlanMoved,
+    /// Watching or moving the scanned file failed, the watcher keeps going.
+    WatchError(String),
+}
+
+/// Pauses and resumes moving in new exports.
+#[derive(Debug, Clone, Default)]
+pub struct WatchControl(Arc<AtomicBool>);
+impl WatchControl {
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+    /// Returns whether watching is now paused.
+    pub fn toggle(&self) -> bool {
+        !self.0.fetch_xor(true, Ordering::Relaxed)
+    }
 }
-pub fn setup(config: &Config) -> Receiver<MsEvent> {
+
+pub fn setup(config: &Config, paths: &Paths) -> (Receiver<MsEvent>, WatchControl) {
     let (tx, rx) = channel();
     let tx2 = tx.clone();
-    std::thread::spawn(move || {
-        loop {
-            tx2.send(MsEvent::Crossterm(event::read())).unwrap();
+    std::thread::spawn(
+        move || {
+            while tx2.send(MsEvent::Crossterm(event::read())).is_ok() {}
+        },
+    );
+    let control = WatchControl::default();
+    spawn_scan(config, paths, tx, control.clone());
+    (rx, control)
+}
+/// Only the plan events, for when there is no terminal to read from.
+pub fn watch(config: &Config, paths: &Paths) -> (Receiver<MsEvent>, WatchControl) {
+    let (tx, rx) = channel();
+    let control = WatchControl::default();
+    spawn_scan(config, paths, tx, control.clone());
+    (rx, control)
+}
+
+fn spawn_scan(config: &Config, paths: &Paths, tx: Sender<MsEvent>, control: WatchControl) {
+    let Some(scan_path) = &config.scan_path else {
+        return;
+    };
+    match ScanPath::new(scan_path) {
+        Ok(scan_path) => {
+            let scanner = Scanner::new(scan_path, paths.plan.clone());
+            std::thread::spawn(move || scan(scanner, tx, control));
+        }
+        Err(e) => {
+            let _ = tx.send(MsEvent::WatchError(e.to_string()));
         }
+    }
+}
+
+/// Watches the directory of the scan path, or polls it when watching isn't
+/// supported, until the receiving end goes away.
+fn scan(mut scanner: Scanner, tx: Sender<MsEvent>, control: WatchControl) {
+    let (wake_tx, wake) = channel();
+    let scan_path = scanner.scan_path.clone();
+    let watched = scan_path.clone();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let relevant = match &event {
+            Ok(event) => event.paths.iter().any(|p| watched.matches(p)),
+            Err(_) => true,
+        };
+        if relevant {
+            let _ = wake_tx.send(event);
+        }
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(scan_path.dir(), RecursiveMode::NonRecursive)?;
+        Ok(watcher)
     });
-    if let Some(scan_path) = config.scan_path.clone() {
-        // let scan_path = scan_path.clone();
-        std::thread::spawn(move || {
-            loop {
-                if move_file(&scan_path, PLAN_PATH).unwrap() {
-                    tx.send(MsEvent::PlanMoved).unwrap();
-                }
-                std::thread::sleep(Duration::from_secs(1));
+    let interval = match &watcher {
+        Ok(_) => WATCH_INTERVAL,
+        Err(e) => {
+            let msg = format!("can't watch {scan_path}, polling instead: {e}");
+            if tx.send(MsEvent::WatchError(msg)).is_err() {
+                return;
             }
-        });
-    }
+            POLL_INTERVAL
+        }
+    };
 
-    rx
+    // the last error sent, so a lasting problem is reported once
+    let mut last_error = None;
+    loop {
+        let error = match wake.recv_timeout(interval) {
+            Ok(Err(e)) => Some(format!("watching {scan_path} failed: {e}")),
+            Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => None,
+            // the watcher is gone, keep polling
+            Err(RecvTimeoutError::Disconnected) => {
+                std::thread::sleep(interval);
+                None
+            }
+        };
+        let event = match error {
+            Some(msg) => Some(MsEvent::WatchError(msg)),
+            None if control.is_paused() => None,
+            None => match scanner.scan() {
+                Ok(true) => Some(MsEvent::PlanMoved),
+                Ok(false) => None,
+                Err(e) => Some(MsEvent::WatchError(format!(
+                    "taking an export from {scan_path} failed: {e}"
+                ))),
+            },
+        };
+        // drop the events of the file settling
+        while wake.try_recv().is_ok() {}
+        let event = match event {
+            Some(MsEvent::WatchError(msg)) if last_error.as_ref() == Some(&msg) => None,
+            Some(MsEvent::WatchError(msg)) => {
+                last_error = Some(msg.clone());
+                Some(MsEvent::WatchError(msg))
+            }
+            Some(MsEvent::PlanMoved) => {
+                last_error = None;
+                Some(MsEvent::PlanMoved)
+            }
+            event => event,
+        };
+        if let Some(event) = event
+            && tx.send(event).is_err()
+        {
+            return;
+        }
+    }
 }
@@ -0,0 +1,237 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyResult, Column, Priority, Progress, SimpleError, Task};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+    /// One VTODO per task with a deadline.
+    Ics,
+    /// One VEVENT per task with a deadline.
+    IcsEvents,
+}
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+            Self::Ics | Self::IcsEvents => "ics",
+        }
+    }
+}
+impl FromStr for ExportFormat {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "ics" | "ics-todo" => Ok(Self::Ics),
+            "ics-event" | "ics-events" => Ok(Self::IcsEvents),
+            _ => Err(format!("not a valid export format: {s}").into()),
+        }
+    }
+}
+
+pub fn column_text(task: &Task, column: Column) -> String {
+    use Column as C;
+    let date = |d: Option<chrono::NaiveDate>| d.map(|d| d.to_string()).unwrap_or_default();
+    match column {
+        C::Name => task.name.clone(),
+        C::Priority => task.priority.to_string(),
+        C::CreateDate => task.create_date.to_string(),
+        C::StartDate => date(task.start_date),
+        C::Deadline => date(task.deadline),
+        C::CompleteDate => date(task.complete_date),
+        C::Progress => task.progress.to_string(),
+        C::Bucket => task.bucket.clone(),
+        C::Labels => task.labels.join(";"),
+        C::AssignedTo => task.assigned_to.join(";"),
+        C::Description => task.description.clone(),
+        C::CreatedBy => task.created_by.clone(),
+        C::Late => task.late.to_string(),
+        C::Items => task
+            .items_completed
+            .map(|(done, all)| format!("{done}/{all}"))
+            .unwrap_or_default(),
+    }
+}
+
+pub fn export(
+    out: &mut impl Write,
+    tasks: &[Task],
+    columns: &[Column],
+    format: ExportFormat,
+    plan_id: &str,
+) -> AnyResult<()> {
+    match format {
+        ExportFormat::Csv => write_csv(out, tasks, columns)?,
+        ExportFormat::Json => write_json(out, tasks, columns)?,
+        ExportFormat::Markdown => write_markdown(out, tasks, columns)?,
+        ExportFormat::Ics => write_ics(out, tasks, plan_id, false)?,
+        ExportFormat::IcsEvents => write_ics(out, tasks, plan_id, true)?,
+    }
+    Ok(())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+fn write_csv(out: &mut impl Write, tasks: &[Task], columns: &[Column]) -> std::io::Result<()> {
+    let header: Vec<String> = columns.iter().map(|c| csv_field(&c.to_string())).collect();
+    writeln!(out, "{}", header.join(","))?;
+    for task in tasks {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|c| csv_field(&column_text(task, *c)))
+            .collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Every task as its serde representation, keeping the id and the fields of
+/// the chosen columns.
+fn write_json(out: &mut impl Write, tasks: &[Task], columns: &[Column]) -> AnyResult<()> {
+    let tasks = tasks
+        .iter()
+        .map(|task| {
+            let mut value = serde_json::to_value(task)?;
+            if let Some(object) = value.as_object_mut() {
+                object.retain(|k, _| k == "id" || columns.iter().any(|c| c.field_name() == k));
+            }
+            Ok(value)
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    serde_json::to_writer_pretty(&mut *out, &tasks)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+fn write_markdown(out: &mut impl Write, tasks: &[Task], columns: &[Column]) -> std::io::Result<()> {
+    let header: Vec<String> = columns.iter().map(ToString::to_string).collect();
+    writeln!(out, "| {} |", header.join(" | "))?;
+    writeln!(out, "|{}", " --- |".repeat(columns.len()))?;
+    for task in tasks {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|c| markdown_cell(&column_text(task, *c)))
+            .collect();
+        writeln!(out, "| {} |", row.join(" | "))?;
+    }
+    Ok(())
+}
+
+fn ics_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+fn ics_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+/// Writes a content line, folded at 75 octets as RFC 5545 asks.
+fn ics_line(out: &mut impl Write, line: &str) -> std::io::Result<()> {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.write_all(b"\r\n ")?;
+            width = 1;
+        }
+        write!(out, "{c}")?;
+        width += c.len_utf8();
+    }
+    out.write_all(b"\r\n")
+}
+/// A calendar of the tasks with a deadline, as to-dos due on the deadline or
+/// as all-day events from the start date to the deadline.
+fn write_ics(
+    out: &mut impl Write,
+    tasks: &[Task],
+    plan_id: &str,
+    events: bool,
+) -> std::io::Result<()> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let component = if events { "VEVENT" } else { "VTODO" };
+    ics_line(out, "BEGIN:VCALENDAR")?;
+    ics_line(out, "VERSION:2.0")?;
+    ics_line(out, "PRODID:-//ms_planner_viewer//EN")?;
+    for task in tasks {
+        let Some(deadline) = task.deadline else {
+            continue;
+        };
+        let mut lines = vec![
+            format!("BEGIN:{component}"),
+            format!("UID:{}@{plan_id}", task.id),
+            format!("DTSTAMP:{stamp}"),
+            format!("SUMMARY:{}", ics_text(&task.name)),
+        ];
+        if !task.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ics_text(&task.description)));
+        }
+        let start = task.start_date.filter(|s| *s <= deadline);
+        if events {
+            let start = start.unwrap_or(deadline);
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ics_date(start)));
+            // the end of an all-day event is exclusive
+            let end = deadline + chrono::Duration::days(1);
+            lines.push(format!("DTEND;VALUE=DATE:{}", ics_date(end)));
+        } else {
+            if let Some(start) = start {
+                lines.push(format!("DTSTART;VALUE=DATE:{}", ics_date(start)));
+            }
+            lines.push(format!("DUE;VALUE=DATE:{}", ics_date(deadline)));
+            let status = match task.progress {
+                Progress::NotStarted => "NEEDS-ACTION",
+                Progress::Ongoing => "IN-PROCESS",
+                Progress::Done => "COMPLETED",
+            };
+            lines.push(format!("STATUS:{status}"));
+            if let (Progress::Done, Some(completed)) = (&task.progress, task.complete_date) {
+                lines.push(format!("COMPLETED:{}T000000Z", ics_date(completed)));
+            }
+            let priority = match task.priority {
+                Priority::Urgent => 1,
+                Priority::Important => 3,
+                Priority::Mid => 5,
+                Priority::Low => 9,
+            };
+            lines.push(format!("PRIORITY:{priority}"));
+        }
+        lines.push(format!("URL:{}", task.to_url(plan_id)));
+        if !task.labels.is_empty() {
+            let labels: Vec<String> = task.labels.iter().map(|l| ics_text(l)).collect();
+            lines.push(format!("CATEGORIES:{}", labels.join(",")));
+        }
+        // the export only has display names, no addresses
+        for person in &task.assigned_to {
+            lines.push(format!(
+                "ATTENDEE;CN=\"{}\":urn:x-planner:{}",
+                person.replace('"', "'"),
+                person.replace('%', "%25").replace(' ', "%20")
+            ));
+        }
+        lines.push(format!("END:{component}"));
+        for line in lines {
+            ics_line(out, &line)?;
+        }
+    }
+    ics_line(out, "END:VCALENDAR")
+}
@@ -13,7 +13,9 @@ use std::str::FromStr;
 use crate::{
     Column, Priority, Progress,
     app::{App, FilterViewMode},
-    config::{MultiTagFilter, Order, TagFilter, TaskFilter, TaskSort},
+    config::{
+        DateFilter, ListSort, MultiTagFilter, Order, TableConfig, TagFilter, TaskFilter, TaskSort,
+    },
     ui::AsText,
 };
 
@@ -37,12 +39,16 @@ pub fn render_filter_column(app: &mut App, f: &mut Frame, area: Rect) {
         }
         FilterViewMode::Columns => {
             let list = List::new(
-                UiColumn::all(&app.config.filter, &app.config.sort)
+                UiColumn::all(&app.config.filter, &app.config.sort, &app.config.table)
                     .into_iter()
                     .map(Into::<Text>::into),
             );
             let list = list
-                .block(Block::bordered().title("Filter"))
+                .block(
+                    Block::bordered()
+                        .title("Filter")
+                        .title_bottom("s: sort, S: add, K/J: move, x: drop, m: list sort, v: show"),
+                )
                 .highlight_symbol("|");
             f.render_stateful_widget(list, area, &mut app.filter_view.state);
         }
@@ -51,6 +57,47 @@ pub fn render_filter_column(app: &mut App, f: &mut Frame, area: Rect) {
             let text = format!("search: {}", input.value());
             f.render_widget(Paragraph::new(text).block(block), area);
         }
+        FilterViewMode::Presets => {
+            let list = List::new(
+                app.config
+                    .presets
+                    .keys()
+                    .map(|name| Text::from(name.as_str())),
+            )
+            .block(
+                Block::bordered()
+                    .title("Presets")
+                    .title_bottom("enter: load, S: save as, r: rename, d: delete"),
+            )
+            .highlight_symbol("|");
+            f.render_stateful_widget(list, area, &mut app.filter_view.state);
+        }
+        FilterViewMode::PresetName(input, old) => {
+            let title = match old {
+                Some(old) => format!("Rename Preset: {old}"),
+                None => "Save Preset As".to_string(),
+            };
+            let text = format!("name: {}", input.value());
+            f.render_widget(
+                Paragraph::new(text).block(Block::bordered().title(title)),
+                area,
+            );
+        }
+        FilterViewMode::DateFilter(input, column) => {
+            let block = Block::bordered().title(format!("Date Filter: {column:?}"));
+            let text = Text::from_iter([
+                format!("range: {}", input.value()),
+                String::new(),
+                "enter to apply, esc to cancel".to_string(),
+                DateFilter::SYNTAX.to_string(),
+            ]);
+            f.render_widget(
+                Paragraph::new(text)
+                    .block(block)
+                    .wrap(ratatui::widgets::Wrap::default()),
+                area,
+            );
+        }
     };
 }
 
@@ -260,18 +307,15 @@ impl TryFrom<UiTagFilter> for MultiTagFilter {
 }
 #[derive(Clone, Copy)]
 pub enum SortType {
-    Sorted(Order),
+    /// Order, rank among the sort keys and how lists compare.
+    Sorted(Order, usize, ListSort),
     Unsorted,
-    Nil,
 }
 impl SortType {
     pub fn new(c: Column, ts: &TaskSort) -> Self {
-        use Column as C;
-        match (c, ts.column == c) {
-            (C::AssignedTo, _) => Self::Nil,
-            (C::Labels, _) => Self::Nil,
-            (_, false) => Self::Unsorted,
-            (_, true) => Self::Sorted(ts.order),
+        match ts.position(c) {
+            None => Self::Unsorted,
+            Some(i) => Self::Sorted(ts.keys[i].order, i + 1, ts.keys[i].by),
         }
     }
 }
@@ -279,6 +323,7 @@ impl SortType {
 pub enum FilterType {
     Tag(bool),
     Text(bool),
+    Date(bool),
     Nil,
 }
 impl FilterType {
@@ -292,10 +337,11 @@ impl FilterType {
             C::Priority => Self::Tag(tf.priority.has_filter()),
             C::Name => Self::Text(!tf.name.is_empty()),
             C::Description => Self::Text(!tf.description.is_empty()),
-            C::Deadline => Self::Nil,
-            C::CreateDate => Self::Nil,
-            C::StartDate => Self::Nil,
-            C::CompleteDate => Self::Nil,
+            C::Deadline => Self::Date(tf.deadline.has_filter()),
+            C::CreateDate => Self::Date(tf.create_date.has_filter()),
+            C::StartDate => Self::Date(tf.start_date.has_filter()),
+            C::CompleteDate => Self::Date(tf.complete_date.has_filter()),
+            C::CreatedBy | C::Late | C::Items => Self::Nil,
         }
     }
 }
@@ -304,9 +350,11 @@ pub struct UiColumn {
     pub sort: SortType,
     pub filtered: FilterType,
     pub column: Column,
+    /// Shown in the task table.
+    pub visible: bool,
 }
 impl UiColumn {
-    pub fn all(tf: &TaskFilter, ts: &TaskSort) -> Vec<UiColumn> {
+    pub fn all(tf: &TaskFilter, ts: &TaskSort, table: &TableConfig) -> Vec<UiColumn> {
         use Column as C;
         vec![
             C::Bucket,
@@ -320,12 +368,16 @@ impl UiColumn {
             C::StartDate,
             C::CompleteDate,
             C::Description,
+            C::CreatedBy,
+            C::Items,
+            C::Late,
         ]
         .into_iter()
         .map(|c| UiColumn {
             sort: SortType::new(c, ts),
             filtered: FilterType::new(c, tf),
             column: c,
+            visible: table.is_visible(c),
         })
         .collect()
     }
@@ -333,26 +385,33 @@ impl UiColumn {
 impl From<UiColumn> for Text<'static> {
     fn from(value: UiColumn) -> Self {
         let sort = match value.sort {
-            SortType::Sorted(Order::Asc) => Span::from("[↑]").green(),
-            SortType::Sorted(Order::Desc) => Span::from("[↓]").red(),
-            SortType::Unsorted => Span::from("[ ]"),
-            _ => Span::from("   "),
+            SortType::Sorted(Order::Asc, i, _) => Span::from(format!("[↑{i}]")).green(),
+            SortType::Sorted(Order::Desc, i, _) => Span::from(format!("[↓{i}]")).red(),
+            SortType::Unsorted => Span::from("[  ]"),
         };
         let mut filtered = false;
         use FilterType as FT;
         let filter = match value.filtered {
-            FT::Tag(true) | FT::Text(true) => {
+            FT::Tag(true) | FT::Text(true) | FT::Date(true) => {
                 filtered = true;
                 Span::from("[*]")
             }
-            FT::Tag(false) | FT::Text(false) => Span::from("[ ]"),
+            FT::Tag(false) | FT::Text(false) | FT::Date(false) => Span::from("[ ]"),
             FT::Nil => Span::from("   "),
         };
 
-        let mut text = Span::from(format!("{:?}", value.column));
+        let mut text = match (value.column, value.sort) {
+            (Column::Labels | Column::AssignedTo, SortType::Sorted(.., by)) => {
+                Span::from(format!("{:?} (by {by:?})", value.column))
+            }
+            (column, _) => Span::from(format!("{column:?}")),
+        };
         if filtered {
             text = text.add_modifier(Modifier::BOLD);
         }
+        if !value.visible {
+            text = text.dark_gray();
+        }
         let span = sort + " ".into() + filter + Span::from(" ") + text;
         span.into()
     }
@@ -0,0 +1,347 @@
+//! Burndown and cumulative flow over the archived exports of a plan.
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph},
+};
+
+use crate::{
+    AnyResult, Progress, SimpleError, app::App, app::filter_tasks, config::Config, history,
+};
+
+/// Counts of the filtered tasks of one archived export.
+#[derive(Debug, Clone, Copy)]
+pub struct FlowPoint {
+    pub date: NaiveDate,
+    pub not_started: usize,
+    pub ongoing: usize,
+    pub done: usize,
+    /// Unchecked checklist items of the unfinished tasks.
+    pub open_items: usize,
+    pub total_items: usize,
+}
+impl FlowPoint {
+    pub fn open_tasks(&self) -> usize {
+        self.not_started + self.ongoing
+    }
+}
+
+/// Reads one count off a point.
+type Count = fn(&FlowPoint) -> usize;
+
+/// One point per archived export of the plan, oldest first, counting the
+/// tasks that match the filter of `config`.
+pub fn series(dir: &Path, plan_id: &str, config: &Config) -> AnyResult<Vec<FlowPoint>> {
+    let mut points = Vec::new();
+    for snapshot in history::snapshots(dir, plan_id)? {
+        let plan = snapshot.load()?;
+        let mut point = FlowPoint {
+            date: snapshot.export_date,
+            not_started: 0,
+            ongoing: 0,
+            done: 0,
+            open_items: 0,
+            total_items: 0,
+        };
+        for task in filter_tasks(config, &plan.tasks) {
+            match task.progress {
+                Progress::NotStarted => point.not_started += 1,
+                Progress::Ongoing => point.ongoing += 1,
+                Progress::Done => point.done += 1,
+            }
+            if let Some((done, all)) = task.items_completed {
+                point.total_items += all;
+                if task.progress != Progress::Done {
+                    point.open_items += all.saturating_sub(done);
+                }
+            }
+        }
+        points.push(point);
+    }
+    Ok(points)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowFormat {
+    Csv,
+    Svg,
+}
+impl FlowFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Svg => "svg",
+        }
+    }
+}
+impl FromStr for FlowFormat {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("not a valid chart format: {s}").into()),
+        }
+    }
+}
+
+/// File the TUI writes the charts to.
+pub fn export_path(format: FlowFormat) -> std::path::PathBuf {
+    std::path::PathBuf::from(format!("plan_flow.{}", format.extension()))
+}
+
+pub fn export(out: &mut impl Write, points: &[FlowPoint], format: FlowFormat) -> AnyResult<()> {
+    match format {
+        FlowFormat::Csv => write_csv(out, points)?,
+        FlowFormat::Svg => write_svg(out, points)?,
+    }
+    Ok(())
+}
+
+fn write_csv(out: &mut impl Write, points: &[FlowPoint]) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "date,not_started,ongoing,done,open_tasks,open_items,total_items"
+    )?;
+    for p in points {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            p.date,
+            p.not_started,
+            p.ongoing,
+            p.done,
+            p.open_tasks(),
+            p.open_items,
+            p.total_items
+        )?;
+    }
+    Ok(())
+}
+
+const SVG_WIDTH: f64 = 800.0;
+const SVG_CHART_HEIGHT: f64 = 300.0;
+const SVG_MARGIN: f64 = 50.0;
+
+/// Maps a day and a count into a chart drawn at the vertical offset `top`.
+struct SvgScale {
+    first: NaiveDate,
+    days: f64,
+    max: f64,
+    top: f64,
+}
+impl SvgScale {
+    fn point(&self, date: NaiveDate, value: usize) -> String {
+        let x = SVG_MARGIN + (date - self.first).num_days() as f64 / self.days * SVG_WIDTH;
+        let y = self.top + SVG_CHART_HEIGHT - value as f64 / self.max * SVG_CHART_HEIGHT;
+        format!("{x:.1},{y:.1}")
+    }
+    fn axes(&self, out: &mut impl Write, title: &str, last: NaiveDate) -> std::io::Result<()> {
+        let (left, right) = (SVG_MARGIN, SVG_MARGIN + SVG_WIDTH);
+        let (top, bottom) = (self.top, self.top + SVG_CHART_HEIGHT);
+        writeln!(
+            out,
+            r#"<text x="{left}" y="{}" font-weight="bold">{title}</text>"#,
+            top - 10.0
+        )?;
+        writeln!(
+            out,
+            r#"<polyline points="{left},{top} {left},{bottom} {right},{bottom}" fill="none" stroke="black"/>"#
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            left - 5.0,
+            top + 5.0,
+            self.max
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{bottom}" text-anchor="end">0</text>"#,
+            left - 5.0
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{left}" y="{}">{}</text>"#,
+            bottom + 20.0,
+            self.first
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{right}" y="{}" text-anchor="end">{last}</text>"#,
+            bottom + 20.0
+        )
+    }
+}
+
+/// Both charts in one image, the burndown above the cumulative flow.
+fn write_svg(out: &mut impl Write, points: &[FlowPoint]) -> std::io::Result<()> {
+    let height = 2.0 * SVG_CHART_HEIGHT + 4.0 * SVG_MARGIN;
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{height}" font-family="sans-serif" font-size="12">"#,
+        SVG_WIDTH + 2.0 * SVG_MARGIN
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let days = ((last.date - first.date).num_days() as f64).max(1.0);
+        let scale = |max: usize, top: f64| SvgScale {
+            first: first.date,
+            days,
+            max: max.max(1) as f64,
+            top,
+        };
+        let line = |scale: &SvgScale, value: Count| {
+            let points: Vec<String> = points
+                .iter()
+                .map(|p| scale.point(p.date, value(p)))
+                .collect();
+            points.join(" ")
+        };
+
+        let max = points
+            .iter()
+            .map(|p| p.open_tasks().max(p.open_items))
+            .max()
+            .unwrap_or(0);
+        let burndown = scale(max, SVG_MARGIN);
+        burndown.axes(out, "Burndown: open tasks and checklist items", last.date)?;
+        let lines: [(Count, &str); 2] = [
+            (FlowPoint::open_tasks, "crimson"),
+            (|p| p.open_items, "orange"),
+        ];
+        for (value, color) in lines {
+            writeln!(
+                out,
+                r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+                line(&burndown, value)
+            )?;
+        }
+
+        let max = points
+            .iter()
+            .map(|p| p.not_started + p.ongoing + p.done)
+            .max()
+            .unwrap_or(0);
+        let flow = scale(max, 3.0 * SVG_MARGIN + SVG_CHART_HEIGHT);
+        // bands from the top down so the lower ones paint over the higher ones
+        let bands: [(Count, &str); 3] = [
+            (|p| p.done + p.ongoing + p.not_started, "lightgray"),
+            (|p| p.done + p.ongoing, "steelblue"),
+            (|p| p.done, "seagreen"),
+        ];
+        for (value, color) in bands {
+            let base = format!("{} {}", flow.point(last.date, 0), flow.point(first.date, 0));
+            writeln!(
+                out,
+                r#"<polygon points="{} {base}" fill="{color}"/>"#,
+                line(&flow, value)
+            )?;
+        }
+        flow.axes(
+            out,
+            "Cumulative flow: done, ongoing and not started",
+            last.date,
+        )?;
+    }
+    writeln!(out, "</svg>")
+}
+
+fn dataset<'a>(name: &'a str, data: &'a [(f64, f64)], color: Color) -> Dataset<'a> {
+    Dataset::default()
+        .name(name)
+        .data(data)
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::new().fg(color))
+}
+fn chart<'a>(
+    title: &'a str,
+    datasets: Vec<Dataset<'a>>,
+    points: &[FlowPoint],
+    max: usize,
+) -> Chart<'a> {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (first.date, last.date),
+        _ => return Chart::new(datasets).block(Block::bordered().title(title)),
+    };
+    let days = (last - first).num_days().max(1) as f64;
+    Chart::new(datasets)
+        .block(Block::bordered().title(title))
+        .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 1)))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, days])
+                .labels([first.to_string(), last.to_string()]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max.max(1) as f64])
+                .labels(["0".to_string(), max.to_string()]),
+        )
+}
+
+pub fn render_flow(app: &mut App, f: &mut Frame, area: Rect) {
+    let Some(points) = &app.flow else {
+        return;
+    };
+    let [burndown_area, flow_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+    if points.is_empty() {
+        let text = Paragraph::new("no archived exports of this plan")
+            .block(Block::bordered().title("burndown (esc: back)"));
+        f.render_widget(text, area);
+        return;
+    }
+    let first = points[0].date;
+    let series = |value: Count| -> Vec<(f64, f64)> {
+        points
+            .iter()
+            .map(|p| ((p.date - first).num_days() as f64, value(p) as f64))
+            .collect()
+    };
+    let open_tasks = series(FlowPoint::open_tasks);
+    let open_items = series(|p| p.open_items);
+    let max = points
+        .iter()
+        .map(|p| p.open_tasks().max(p.open_items))
+        .max()
+        .unwrap_or(0);
+    let burndown = chart(
+        "burndown (c: export csv, s: export svg, esc: back)",
+        vec![
+            dataset("open tasks", &open_tasks, Color::LightRed),
+            dataset("open checklist items", &open_items, Color::Yellow),
+        ],
+        points,
+        max,
+    );
+    f.render_widget(burndown, burndown_area);
+
+    let all = series(|p| p.done + p.ongoing + p.not_started);
+    let started = series(|p| p.done + p.ongoing);
+    let done = series(|p| p.done);
+    let max = points
+        .iter()
+        .map(|p| p.done + p.ongoing + p.not_started)
+        .max()
+        .unwrap_or(0);
+    let flow = chart(
+        "cumulative flow",
+        vec![
+            dataset("not started", &all, Color::Gray),
+            dataset("ongoing", &started, Color::Blue),
+            dataset("done", &done, Color::Green),
+        ],
+        points,
+        max,
+    );
+    f.render_widget(flow, flow_area);
+}
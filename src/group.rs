@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{Priority, Progress, SimpleError, Task, config};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Bucket,
+    Progress,
+    Priority,
+    Assignee,
+    Label,
+    DeadlineWeek,
+}
+impl GroupBy {
+    /// Cycles through no grouping and every group.
+    pub fn next(value: Option<Self>) -> Option<Self> {
+        match value {
+            None => Some(Self::Bucket),
+            Some(Self::Bucket) => Some(Self::Progress),
+            Some(Self::Progress) => Some(Self::Priority),
+            Some(Self::Priority) => Some(Self::Assignee),
+            Some(Self::Assignee) => Some(Self::Label),
+            Some(Self::Label) => Some(Self::DeadlineWeek),
+            Some(Self::DeadlineWeek) => None,
+        }
+    }
+}
+impl FromStr for GroupBy {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bucket" => Ok(Self::Bucket),
+            "progress" => Ok(Self::Progress),
+            "priority" => Ok(Self::Priority),
+            "assignee" | "assigned" | "assigned_to" => Ok(Self::Assignee),
+            "label" | "labels" => Ok(Self::Label),
+            "deadline" | "week" | "deadline_week" => Ok(Self::DeadlineWeek),
+            _ => Err(format!("not a valid group: {s}").into()),
+        }
+    }
+}
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+pub struct Group {
+    pub title: String,
+    /// Indexes into the tasks the groups were built from, in their order.
+    pub tasks: Vec<usize>,
+}
+
+const NONE_TITLE: &str = "(none)";
+const NO_DEADLINE_TITLE: &str = "no deadline";
+
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// The tasks split into groups, keeping their order within each group. Tasks
+/// with several labels or assignees show up in each of their groups, tasks
+/// without any in a trailing "(none)" group.
+pub fn groups(tasks: &[Task], group_by: GroupBy) -> Vec<Group> {
+    let keys = |task: &Task| -> Vec<String> {
+        match group_by {
+            GroupBy::Bucket => vec![task.bucket.clone()],
+            GroupBy::Progress => vec![task.progress.to_string()],
+            GroupBy::Priority => vec![task.priority.to_string()],
+            GroupBy::Assignee if task.assigned_to.is_empty() => vec![NONE_TITLE.to_string()],
+            GroupBy::Assignee => task.assigned_to.clone(),
+            GroupBy::Label if task.labels.is_empty() => vec![NONE_TITLE.to_string()],
+            GroupBy::Label => task.labels.clone(),
+            GroupBy::DeadlineWeek => vec![match task.deadline {
+                Some(d) => format!("week of {}", week_start(d)),
+                None => NO_DEADLINE_TITLE.to_string(),
+            }],
+        }
+    };
+    let keys: Vec<Vec<String>> = tasks.iter().map(keys).collect();
+    let titles: Vec<String> = match group_by {
+        GroupBy::Progress => Progress::items().iter().map(ToString::to_string).collect(),
+        GroupBy::Priority => Priority::items().iter().map(ToString::to_string).collect(),
+        _ => {
+            // the dates of the week titles sort the same as their text
+            let mut titles = config::get_unique_strings(keys.iter().flatten());
+            titles.sort_by_key(|t| (t == NONE_TITLE || t == NO_DEADLINE_TITLE, t.clone()));
+            titles
+        }
+    };
+    titles
+        .into_iter()
+        .map(|title| Group {
+            tasks: (0..tasks.len())
+                .filter(|&i| keys[i].contains(&title))
+                .collect(),
+            title,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableRow {
+    /// Header of the group with this index.
+    Header(usize),
+    /// Task with this index, under the group with the given index.
+    Task(usize, usize),
+}
+
+/// The rows of the task table, without headers when not grouping and
+/// without empty groups or the tasks of collapsed ones.
+pub fn table_rows(
+    groups: Option<&[Group]>,
+    tasks: usize,
+    collapsed: &HashSet<String>,
+) -> Vec<TableRow> {
+    let Some(groups) = groups else {
+        return (0..tasks).map(|i| TableRow::Task(i, 0)).collect();
+    };
+    let mut rows = Vec::new();
+    for (g, group) in groups
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| !g.tasks.is_empty())
+    {
+        rows.push(TableRow::Header(g));
+        if !collapsed.contains(&group.title) {
+            rows.extend(group.tasks.iter().map(|&t| TableRow::Task(t, g)));
+        }
+    }
+    rows
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::Plan;
+
+const DATE_FMT: &str = "%Y-%m-%d";
+
+/// An archived export of a plan.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub export_date: NaiveDate,
+    pub path: PathBuf,
+}
+impl Snapshot {
+    pub fn load(&self) -> crate::parse_xlsx::ParseResult<Plan> {
+        Plan::from_path_lenient(&self.path).map(|(plan, _)| plan)
+    }
+}
+
+/// The history directory of a plan. The id comes from the export, so it must
+/// name a single directory inside `dir`.
+fn plan_dir(dir: &Path, plan_id: &str) -> io::Result<PathBuf> {
+    let mut components = Path::new(plan_id).components();
+    let single = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    if !single || plan_id.contains(['/', '\\']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("plan id can't be used as a directory name: {plan_id:?}"),
+        ));
+    }
+    Ok(dir.join(plan_id))
+}
+
+/// Copies the export at `src` into the history, one file per plan and export
+/// date. A later export of the same day replaces the earlier one.
+pub fn archive(plan: &Plan, src: &Path, dir: &Path) -> io::Result<PathBuf> {
+    let dir = plan_dir(dir, &plan.id)?;
+    fs::create_dir_all(&dir)?;
+    let extension = src.extension().and_then(|e| e.to_str()).unwrap_or("xlsx");
+    let dst = dir.join(format!("{}.{extension}", plan.export_date.format(DATE_FMT)));
+    fs::copy(src, &dst)?;
+    Ok(dst)
+}
+
+/// All archived exports of a plan, oldest first.
+pub fn snapshots(dir: &Path, plan_id: &str) -> io::Result<Vec<Snapshot>> {
+    let dir = plan_dir(dir, plan_id)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_stem()?.to_str()?;
+            let export_date = NaiveDate::parse_from_str(stem, DATE_FMT).ok()?;
+            Some(Snapshot { export_date, path })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| s.export_date);
+    Ok(snapshots)
+}
+
+/// The newest archived export from before `plan` was exported.
+pub fn previous(dir: &Path, plan: &Plan) -> io::Result<Option<Snapshot>> {
+    Ok(snapshots(dir, &plan.id)?
+        .into_iter()
+        .rfind(|s| s.export_date < plan.export_date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_dir_stays_inside_the_history() {
+        let dir = Path::new("/history");
+        assert_eq!(
+            plan_dir(dir, "aBc-12_x").unwrap(),
+            PathBuf::from("/history/aBc-12_x")
+        );
+        for id in ["", ".", "..", "../x", "a/b", "a\\b", "/etc", "x/.."] {
+            assert!(plan_dir(dir, id).is_err(), "{id:?}");
+        }
+    }
+}
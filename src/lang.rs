@@ -1,13 +1,266 @@
-pub mod nl {
-    pub const SHEET_TASKS: &str = "Taken";
-    pub const SHEET_INFO: &str = "Plannaam ";
+/// Strings of a Planner export that depend on the language Planner runs in.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub code: &'static str,
+    pub sheet_tasks: &'static str,
+    pub sheet_info: &'static str,
+    pub date_fmt: &'static str,
     // prio
-    pub const PRIO_LOW: &str = "Laag";
-    pub const PRIO_MID: &str = "Gemiddeld";
-    pub const PRIO_URGENT: &str = "Belangrijk";
-    pub const PRIO_IMPORTANT: &str = "Dringend";
+    pub prio_low: &'static str,
+    pub prio_mid: &'static str,
+    pub prio_urgent: &'static str,
+    pub prio_important: &'static str,
     // progress
-    pub const PROGRESS_NOT_STARTED: &str = "Niet gestart";
-    pub const PROGRESS_ONGOING: &str = "Wordt uitgevoerd";
-    pub const PROGRESS_DONE: &str = "Voltooid";
+    pub progress_not_started: &'static str,
+    pub progress_ongoing: &'static str,
+    pub progress_done: &'static str,
+    // header row of the tasks sheet, in export order
+    pub headers: [&'static str; HEADERS_LEN],
+}
+pub const HEADERS_LEN: usize = 18;
+
+pub const NL: Locale = Locale {
+    code: "nl",
+    sheet_tasks: "Taken",
+    sheet_info: "Plannaam ",
+    date_fmt: "%d-%m-%Y",
+    prio_low: "Laag",
+    prio_mid: "Gemiddeld",
+    prio_urgent: "Dringend",
+    prio_important: "Belangrijk",
+    progress_not_started: "Niet gestart",
+    progress_ongoing: "Wordt uitgevoerd",
+    progress_done: "Voltooid",
+    headers: [
+        "Taak-id",
+        "Taaknaam",
+        "Bucketnaam",
+        "Voortgang",
+        "Prioriteit",
+        "Toegewezen aan",
+        "Gemaakt door",
+        "Gemaakt op",
+        "Begindatum",
+        "Vervaldatum",
+        "Is terugkerend",
+        "Te laat",
+        "Voltooid op",
+        "Voltooid door",
+        "Voltooide controlelijstitems",
+        "Controlelijstitems",
+        "Labels",
+        "Beschrijving",
+    ],
+};
+
+pub const EN: Locale = Locale {
+    code: "en",
+    sheet_tasks: "Tasks",
+    sheet_info: "Plan name ",
+    date_fmt: "%m/%d/%Y",
+    prio_low: "Low",
+    prio_mid: "Medium",
+    prio_urgent: "Urgent",
+    prio_important: "Important",
+    progress_not_started: "Not started",
+    progress_ongoing: "In progress",
+    progress_done: "Completed",
+    headers: [
+        "Task ID",
+        "Task Name",
+        "Bucket Name",
+        "Progress",
+        "Priority",
+        "Assigned To",
+        "Created By",
+        "Created Date",
+        "Start Date",
+        "Due Date",
+        "Is Recurring",
+        "Late",
+        "Completed Date",
+        "Completed By",
+        "Completed Checklist Items",
+        "Checklist Items",
+        "Labels",
+        "Description",
+    ],
+};
+
+pub const DE: Locale = Locale {
+    code: "de",
+    sheet_tasks: "Aufgaben",
+    sheet_info: "Planname ",
+    date_fmt: "%d.%m.%Y",
+    prio_low: "Niedrig",
+    prio_mid: "Mittel",
+    prio_urgent: "Dringend",
+    prio_important: "Wichtig",
+    progress_not_started: "Nicht begonnen",
+    progress_ongoing: "In Bearbeitung",
+    progress_done: "Abgeschlossen",
+    headers: [
+        "Aufgaben-ID",
+        "Aufgabenname",
+        "Bucketname",
+        "Status",
+        "Priorität",
+        "Zugewiesen an",
+        "Erstellt von",
+        "Erstellungsdatum",
+        "Startdatum",
+        "Fälligkeitsdatum",
+        "Ist wiederkehrend",
+        "Verspätet",
+        "Abschlussdatum",
+        "Abgeschlossen von",
+        "Abgeschlossene Checklistenelemente",
+        "Checklistenelemente",
+        "Bezeichnungen",
+        "Beschreibung",
+    ],
+};
+
+pub const FR: Locale = Locale {
+    code: "fr",
+    sheet_tasks: "Tâches",
+    sheet_info: "Nom du plan ",
+    date_fmt: "%d/%m/%Y",
+    prio_low: "Faible",
+    prio_mid: "Moyenne",
+    prio_urgent: "Urgente",
+    prio_important: "Importante",
+    progress_not_started: "Non démarrée",
+    progress_ongoing: "En cours",
+    progress_done: "Terminée",
+    headers: [
+        "ID de tâche",
+        "Nom de la tâche",
+        "Nom du compartiment",
+        "Progression",
+        "Priorité",
+        "Attribué à",
+        "Créé par",
+        "Date de création",
+        "Date de début",
+        "Date d'échéance",
+        "Est récurrente",
+        "En retard",
+        "Date de fin",
+        "Terminée par",
+        "Éléments de la liste de contrôle terminés",
+        "Éléments de la liste de contrôle",
+        "Étiquettes",
+        "Description",
+    ],
+};
+
+pub const ALL: [&Locale; 4] = [&NL, &EN, &DE, &FR];
+
+impl Locale {
+    /// Picks the locale of an export from its sheets, given as the sheet name
+    /// and the first row of that sheet. Sheet names weigh heavier than headers.
+    pub fn detect(sheets: &[(String, Vec<String>)]) -> Option<&'static Locale> {
+        ALL.into_iter()
+            .map(|l| (l.score(sheets), l))
+            .filter(|(score, _)| *score > 0)
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, l)| l)
+    }
+    fn score(&self, sheets: &[(String, Vec<String>)]) -> usize {
+        sheets
+            .iter()
+            .map(|(name, header)| {
+                let name = name.trim();
+                if name == self.sheet_tasks.trim() || name == self.sheet_info.trim() {
+                    HEADERS_LEN + self.header_matches(header)
+                } else {
+                    self.header_matches(header)
+                }
+            })
+            .sum()
+    }
+    pub fn header_matches(&self, header: &[String]) -> usize {
+        header
+            .iter()
+            .filter(|h| {
+                self.headers
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(h.trim()))
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Priority, Progress};
+
+    #[test]
+    fn priorities_per_locale() {
+        let cases: [(&Locale, [&str; 4]); 4] = [
+            (&NL, ["Dringend", "Belangrijk", "Gemiddeld", "Laag"]),
+            (&EN, ["Urgent", "Important", "Medium", "Low"]),
+            (&DE, ["Dringend", "Wichtig", "Mittel", "Niedrig"]),
+            (&FR, ["Urgente", "Importante", "Moyenne", "Faible"]),
+        ];
+        for (locale, words) in cases {
+            for (word, priority) in words.into_iter().zip(Priority::items()) {
+                assert_eq!(
+                    Priority::from_locale(word, locale),
+                    Some(priority),
+                    "{} {word}",
+                    locale.code
+                );
+            }
+            assert_eq!(Priority::from_locale("nonsense", locale), None);
+        }
+    }
+
+    #[test]
+    fn same_word_same_priority_in_every_locale() {
+        for a in ALL {
+            for b in ALL {
+                for word in [a.prio_low, a.prio_mid, a.prio_important, a.prio_urgent] {
+                    if let Some(p) = Priority::from_locale(word, b) {
+                        assert_eq!(Priority::from_locale(word, a), Some(p), "{word}");
+                    }
+                }
+            }
+        }
+        assert_eq!(Priority::try_from("Dringend").unwrap(), Priority::Urgent);
+    }
+
+    #[test]
+    fn progress_per_locale() {
+        for locale in ALL {
+            let words = [
+                locale.progress_not_started,
+                locale.progress_ongoing,
+                locale.progress_done,
+            ];
+            for (word, progress) in words.into_iter().zip(Progress::items()) {
+                assert_eq!(Progress::from_locale(word, locale), Some(progress));
+            }
+        }
+    }
+
+    fn sheets(locale: &Locale, info: &str, tasks: &str) -> Vec<(String, Vec<String>)> {
+        let header = locale.headers.iter().map(|h| h.to_string()).collect();
+        vec![(info.to_string(), Vec::new()), (tasks.to_string(), header)]
+    }
+
+    #[test]
+    fn detect() {
+        for locale in ALL {
+            let exported = sheets(locale, locale.sheet_info, locale.sheet_tasks);
+            assert_eq!(Locale::detect(&exported), Some(locale), "{}", locale.code);
+            // renamed sheets still go by the headers
+            let renamed = sheets(locale, "Sheet1", "Sheet2");
+            assert_eq!(Locale::detect(&renamed), Some(locale), "{}", locale.code);
+        }
+        let unknown = vec![("Blad1".to_string(), vec!["a".to_string(), "b".to_string()])];
+        assert_eq!(Locale::detect(&unknown), None);
+    }
 }
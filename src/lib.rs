@@ -1,21 +1,33 @@
 pub mod app;
+pub mod board;
+pub mod calendar;
+pub mod cli;
 pub mod config;
+pub mod dashboard;
+pub mod diff;
 pub mod event;
+pub mod export;
 pub mod filter;
+pub mod flow;
+pub mod group;
+pub mod history;
 pub mod lang;
 pub mod parse_xlsx;
+pub mod paths;
 pub mod plan;
+pub mod query;
+pub mod report;
 pub mod scan_dir;
+pub mod serve;
+pub mod timeline;
 pub mod ui;
+pub mod workload;
 
 pub type AnyResult<T> = anyhow::Result<T>;
 pub type SimpleResult<T> = Result<T, SimpleError>;
 pub use common::SimpleError;
 pub use plan::{Column, Plan, Priority, Progress, Task};
 
-pub const PLAN_PATH: &str = "./resources/plan.xlsx";
-pub const CONFIG_PATH: &str = "./resources/config.toml";
-
 pub mod common {
     #[derive(thiserror::Error, Debug)]
     #[error("{0}")]
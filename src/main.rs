@@ -1,20 +1,54 @@
+use clap::Parser;
 use ms_planner_viewer::{
-    CONFIG_PATH, PLAN_PATH, Plan, app::App, config::Config, scan_dir::move_file,
+    Plan,
+    app::App,
+    cli::{self, Cli, Command},
+    config::Config,
+    history,
+    paths::Paths,
 };
 fn main() -> anyhow::Result<()> {
-    let plan = Plan::from_path(PLAN_PATH)?;
-    let config = if !std::fs::exists(CONFIG_PATH)? {
-        let config = Config::default();
-        config.to_file(CONFIG_PATH)?;
-        config
-    } else {
-        Config::from_file(CONFIG_PATH)?
+    let cli = Cli::parse();
+    let paths = Paths::resolve(cli.plan, cli.config)?;
+    let (plan, warnings) = Plan::from_path_lenient(&paths.plan)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", paths.plan.display()))?;
+    // the subcommands only read, except serve which keeps the history like the TUI
+    let archived = match &cli.command {
+        None | Some(Command::Serve { .. }) => Some(
+            paths
+                .history()
+                .and_then(|dir| history::archive(&plan, &paths.plan, &dir)),
+        ),
+        Some(_) => None,
     };
-    // let _ = dbg!(move_file(config.scan_path.unwrap(), PLAN_PATH))?;
-    // Ok(())
+    let mut config = match cli.command {
+        Some(_) => Config::load(&paths.config)?,
+        None => Config::load_or_create(&paths.config)?,
+    };
+    if let Some(preset) = &cli.preset {
+        config.apply_preset(preset)?;
+    }
+
+    if let Some(mut command) = cli.command {
+        if let Command::List(args) | Command::Report(args) = &mut command {
+            args.saved |= cli.preset.is_some();
+        }
+        for warning in &warnings {
+            eprintln!("warning: {warning}");
+        }
+        if let Some(Err(e)) = archived {
+            eprintln!("warning: archiving the plan failed: {e}");
+        }
+        return cli::run(command, &plan, &config, &paths);
+    }
 
     let terminal = ratatui::init();
-    let app_result = App::new(plan, config).run(terminal);
+    let mut app = App::new(plan, config, paths);
+    app.add_parse_warnings(&warnings);
+    if let Some(Err(e)) = archived {
+        app.add_error_msg(&format!("archiving the plan failed: {e}"));
+    }
+    let app_result = app.run(terminal);
     ratatui::restore();
     app_result
 }
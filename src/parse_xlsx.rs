@@ -1,42 +1,241 @@
-use crate::{AnyResult, lang};
-use crate::{Plan, Task};
+use crate::lang::{HEADERS_LEN, Locale};
+use crate::{Plan, Priority, Progress, Task};
 use calamine::{self, DataType, open_workbook_auto};
 use calamine::{Data, Reader};
 use chrono::NaiveDate;
 use std::path::Path;
-const DATA_FMT: &str = "%d-%m-%Y";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("unable to open workbook: {0}")]
+    Open(#[from] calamine::Error),
+    #[error("export language not recognised")]
+    UnknownLocale,
+    #[error("missing sheet: {0:?}")]
+    MissingSheet(String),
+    #[error("missing plan info: {0}")]
+    MissingInfo(&'static str),
+    #[error("missing column: {0:?}")]
+    MissingColumn(Field),
+    #[error("missing column: {0:?}, its values are left empty")]
+    MissingOptionalColumn(Field),
+    #[error("row {row}, column {column}: cell is not text: {value:?}")]
+    NotText {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    #[error("row {row}, {field:?}: bad date {value:?}")]
+    BadDate {
+        row: usize,
+        field: Field,
+        value: String,
+    },
+    #[error("row {row}, {field:?}: unknown value {value:?}")]
+    UnknownValue {
+        row: usize,
+        field: Field,
+        value: String,
+    },
+    #[error("row {row}: malformed checklist count {value:?}")]
+    BadChecklist { row: usize, value: String },
+    #[error("row {row}: empty {field:?}, row skipped")]
+    EmptyRequired { row: usize, field: Field },
+}
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// A `Task` field as found in the header row of the tasks sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Id,
+    Name,
+    Bucket,
+    Progress,
+    Priority,
+    AssignedTo,
+    CreatedBy,
+    CreateDate,
+    StartDate,
+    Deadline,
+    Recurring,
+    Late,
+    CompleteDate,
+    CompletedBy,
+    ItemsCompleted,
+    Items,
+    Labels,
+    Description,
+}
+impl Field {
+    /// In the same order as `Locale::headers`.
+    pub const ALL: [Field; HEADERS_LEN] = [
+        Field::Id,
+        Field::Name,
+        Field::Bucket,
+        Field::Progress,
+        Field::Priority,
+        Field::AssignedTo,
+        Field::CreatedBy,
+        Field::CreateDate,
+        Field::StartDate,
+        Field::Deadline,
+        Field::Recurring,
+        Field::Late,
+        Field::CompleteDate,
+        Field::CompletedBy,
+        Field::ItemsCompleted,
+        Field::Items,
+        Field::Labels,
+        Field::Description,
+    ];
+    const REQUIRED: [Field; 2] = [Field::Id, Field::Name];
+    fn from_header(header: &str, locale: &Locale) -> Option<Self> {
+        let header = header.trim();
+        locale
+            .headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(header))
+            .map(|i| Self::ALL[i])
+    }
+}
+
+/// Maps the columns of the tasks sheet to `Task` fields by header name.
+#[derive(Debug, Clone)]
+pub struct HeaderMap {
+    columns: Vec<(String, Option<Field>)>,
+}
+impl HeaderMap {
+    pub fn new(header: &[Data], locale: &Locale) -> ParseResult<Self> {
+        let columns: Vec<(String, Option<Field>)> = header
+            .iter()
+            .map(|cell| {
+                let name = cell.to_string();
+                let field = Field::from_header(&name, locale);
+                (name, field)
+            })
+            .collect();
+        for required in Field::REQUIRED {
+            if !columns.iter().any(|(_, f)| *f == Some(required)) {
+                return Err(ParseError::MissingColumn(required));
+            }
+        }
+        Ok(Self { columns })
+    }
+    fn index(&self, field: Field) -> Option<usize> {
+        self.columns.iter().position(|(_, f)| *f == Some(field))
+    }
+    /// Fields without a column, the required ones are never missing.
+    pub fn missing(&self) -> impl Iterator<Item = Field> + '_ {
+        Field::ALL
+            .into_iter()
+            .filter(|field| self.index(*field).is_none())
+    }
+}
 
 impl Task {
-    pub fn parse(data: &[Data]) -> AnyResult<Self> {
-        let str_data: Result<Vec<String>, usize> = data
+    /// Parses one row of the tasks sheet, `row` being its 1-based spreadsheet
+    /// row. Bad cells are defaulted and reported in `warnings`; a row without
+    /// id or name is an error.
+    pub fn parse(
+        data: &[Data],
+        row: usize,
+        header: &HeaderMap,
+        locale: &Locale,
+        warnings: &mut Vec<ParseError>,
+    ) -> ParseResult<Self> {
+        let str_data: Vec<String> = data
             .iter()
             .enumerate()
             .map(|(i, cell)| {
                 cell.as_string()
                     .or(cell.is_empty().then_some(String::new()))
-                    .ok_or(i)
+                    .unwrap_or_else(|| {
+                        let value = cell.to_string();
+                        warnings.push(ParseError::NotText {
+                            row,
+                            column: i + 1,
+                            value: value.clone(),
+                        });
+                        value
+                    })
             })
             .collect();
-        let str_data: Vec<String> =
-            str_data.map_err(|i| anyhow::anyhow!("{:?} is not string", data[i]))?;
-        let id = str_data[0].clone();
-        let name = str_data[1].clone();
-        let bucket = str_data[2].clone();
-        let progress = str_data[3].as_str().into();
-        let priority = str_data[4].as_str().into();
-        let assigned_to = to_string_list(&str_data[5]);
-        let created_by = str_data[6].clone();
-        let create_date = NaiveDate::parse_from_str(&str_data[7], DATA_FMT)?;
-        let start_date = to_option_date(&str_data[8])?;
-        let deadline = to_option_date(&str_data[9])?;
-        let recurring = (str_data[10] != "false").then_some(str_data[10].clone());
-        let late = str_data[11] == "true";
-        let complete_date = to_option_date(&str_data[12])?;
-        let completed_by = str_data[13].clone();
-        let items_completed = to_usizes(&str_data[14]);
-        let items: Vec<String> = to_string_list(&str_data[15]);
-        let labels: Vec<String> = to_string_list(&str_data[16]);
-        let description = str_data[17].clone();
+        let get = |field: Field| -> &str {
+            header
+                .index(field)
+                .and_then(|i| str_data.get(i))
+                .map_or("", String::as_str)
+        };
+        for field in Field::REQUIRED {
+            if get(field).is_empty() {
+                return Err(ParseError::EmptyRequired { row, field });
+            }
+        }
+        let mut date = |field: Field| match to_option_date(get(field), locale.date_fmt) {
+            Ok(date) => date,
+            Err(()) => {
+                warnings.push(ParseError::BadDate {
+                    row,
+                    field,
+                    value: get(field).to_string(),
+                });
+                None
+            }
+        };
+        let create_date = date(Field::CreateDate).unwrap_or_default();
+        let start_date = date(Field::StartDate);
+        let deadline = date(Field::Deadline);
+        let complete_date = date(Field::CompleteDate);
+        let progress = match get(Field::Progress) {
+            "" => Progress::default(),
+            s => Progress::from_locale(s, locale).unwrap_or_else(|| {
+                warnings.push(ParseError::UnknownValue {
+                    row,
+                    field: Field::Progress,
+                    value: s.to_string(),
+                });
+                Progress::default()
+            }),
+        };
+        let priority = match get(Field::Priority) {
+            "" => Priority::default(),
+            s => Priority::from_locale(s, locale).unwrap_or_else(|| {
+                warnings.push(ParseError::UnknownValue {
+                    row,
+                    field: Field::Priority,
+                    value: s.to_string(),
+                });
+                Priority::default()
+            }),
+        };
+        let items_completed = to_usizes(get(Field::ItemsCompleted)).unwrap_or_else(|()| {
+            warnings.push(ParseError::BadChecklist {
+                row,
+                value: get(Field::ItemsCompleted).to_string(),
+            });
+            None
+        });
+        let id = get(Field::Id).to_string();
+        let name = get(Field::Name).to_string();
+        let bucket = get(Field::Bucket).to_string();
+        let assigned_to = to_string_list(get(Field::AssignedTo));
+        let created_by = get(Field::CreatedBy).to_string();
+        let recurring = match get(Field::Recurring) {
+            "" | "false" => None,
+            s => Some(s.to_string()),
+        };
+        let late = get(Field::Late) == "true";
+        let completed_by = get(Field::CompletedBy).to_string();
+        let items: Vec<String> = to_string_list(get(Field::Items));
+        let labels: Vec<String> = to_string_list(get(Field::Labels));
+        let description = get(Field::Description).to_string();
+        let extra = header
+            .columns
+            .iter()
+            .zip(&str_data)
+            .filter(|((name, field), _)| field.is_none() && !name.is_empty())
+            .map(|((name, _), value)| (name.clone(), value.clone()))
+            .collect();
         Ok(Task {
             id,
             name,
@@ -56,6 +255,7 @@ impl Task {
             items,
             labels,
             description,
+            extra,
         })
     }
     pub fn to_url(&self, plan_id: &str) -> String {
@@ -65,14 +265,17 @@ impl Task {
         )
     }
 }
-fn to_usizes(slice: &str) -> Option<(usize, usize)> {
+fn to_usizes(slice: &str) -> Result<Option<(usize, usize)>, ()> {
     if slice.is_empty() {
-        None
+        Ok(None)
     } else {
-        let mut iter = slice.split('/');
-        let a: usize = iter.next().unwrap().parse().unwrap();
-        let b: usize = iter.next().unwrap().parse().unwrap();
-        Some((a, b))
+        let (a, b) = slice.split_once('/').ok_or(())?;
+        let a: usize = a.trim().parse().map_err(|_| ())?;
+        let b: usize = b.trim().parse().map_err(|_| ())?;
+        if a > b {
+            return Err(());
+        }
+        Ok(Some((a, b)))
     }
 }
 #[inline]
@@ -83,35 +286,151 @@ fn to_string_list(slice: &str) -> Vec<String> {
         slice.split(';').map(str::to_string).collect()
     }
 }
-fn to_option_date(slice: &str) -> AnyResult<Option<NaiveDate>> {
+fn to_option_date(slice: &str, fmt: &str) -> Result<Option<NaiveDate>, ()> {
     if !slice.is_empty() {
-        Ok(Some(NaiveDate::parse_from_str(slice, DATA_FMT)?))
+        Ok(Some(NaiveDate::parse_from_str(slice, fmt).map_err(|_| ())?))
     } else {
         Ok(None)
     }
 }
 impl Plan {
-    pub fn from_path(path: impl AsRef<Path>) -> AnyResult<Self> {
-        let mut workbook = open_workbook_auto(path).expect("lol");
-        let info = workbook.worksheet_range(lang::nl::SHEET_INFO).unwrap();
-        let range = workbook.worksheet_range(lang::nl::SHEET_TASKS).unwrap();
+    /// Parses an export, failing on the first bad cell.
+    pub fn from_path(path: impl AsRef<Path>) -> ParseResult<Self> {
+        let (plan, mut warnings) = Self::from_path_lenient(path)?;
+        if warnings.is_empty() {
+            Ok(plan)
+        } else {
+            Err(warnings.remove(0))
+        }
+    }
+    /// Parses an export, defaulting bad cells and skipping rows without id or
+    /// name. Everything that was defaulted or skipped is returned as warnings.
+    pub fn from_path_lenient(path: impl AsRef<Path>) -> ParseResult<(Self, Vec<ParseError>)> {
+        let mut workbook = open_workbook_auto(path)?;
+        let sheets: Vec<(String, Vec<String>)> = workbook
+            .worksheets()
+            .into_iter()
+            .map(|(name, range)| {
+                let header = range
+                    .rows()
+                    .next()
+                    .map(|row| row.iter().map(|c| c.to_string()).collect())
+                    .unwrap_or_default();
+                (name, header)
+            })
+            .collect();
+        let locale = Locale::detect(&sheets).ok_or(ParseError::UnknownLocale)?;
+        let mut sheet = |wanted: &str| {
+            let name = sheets
+                .iter()
+                .map(|(name, _)| name.clone())
+                .find(|name| name.trim() == wanted.trim())
+                .ok_or_else(|| ParseError::MissingSheet(wanted.to_string()))?;
+            workbook
+                .worksheet_range(&name)
+                .map_err(|_| ParseError::MissingSheet(wanted.to_string()))
+        };
+        let info = sheet(locale.sheet_info)?;
+        let range = sheet(locale.sheet_tasks)?;
         let mut info = info.rows();
-        let name = info.next().unwrap()[1].as_string().unwrap();
-        let id = info.next().unwrap()[1].as_string().unwrap();
-        let export_date = info.next().unwrap()[1].as_string().unwrap();
-        let export_date = NaiveDate::parse_from_str(&export_date, DATA_FMT)?;
+        let mut info_value = |what: &'static str| {
+            info.next()
+                .and_then(|row| row.get(1))
+                .and_then(|cell| cell.as_string())
+                .ok_or(ParseError::MissingInfo(what))
+        };
+        let name = info_value("plan name")?;
+        let id = info_value("plan id")?;
+        let export_date = info_value("export date")?;
+        let export_date = NaiveDate::parse_from_str(&export_date, locale.date_fmt)
+            .map_err(|_| ParseError::MissingInfo("export date"))?;
+        let mut rows = range.rows();
+        let header = HeaderMap::new(rows.next().unwrap_or_default(), locale)?;
         let mut tasks: Vec<Task> = Vec::new();
-        for (i, data) in range.rows().enumerate() {
-            if i == 0 {
-                continue;
+        let mut warnings: Vec<ParseError> = header
+            .missing()
+            .map(ParseError::MissingOptionalColumn)
+            .collect();
+        for (i, data) in rows.enumerate() {
+            match Task::parse(data, i + 2, &header, locale, &mut warnings) {
+                Ok(task) => tasks.push(task),
+                Err(e) => warnings.push(e),
             }
-            tasks.push(Task::parse(data)?);
         }
-        Ok(Plan {
+        let plan = Plan {
             id,
             name,
             export_date,
             tasks,
-        })
+        };
+        Ok((plan, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::{DE, EN};
+
+    fn header(names: &[&str]) -> Vec<Data> {
+        names.iter().map(|n| Data::String(n.to_string())).collect()
+    }
+
+    #[test]
+    fn field_from_header() {
+        assert_eq!(Field::from_header("Task ID", &EN), Some(Field::Id));
+        assert_eq!(Field::from_header(" due date ", &EN), Some(Field::Deadline));
+        assert_eq!(
+            Field::from_header("Fälligkeitsdatum", &DE),
+            Some(Field::Deadline)
+        );
+        assert_eq!(Field::from_header("Fälligkeitsdatum", &EN), None);
+        assert_eq!(Field::from_header("Sprint", &EN), None);
+        for (name, field) in EN.headers.iter().zip(Field::ALL) {
+            assert_eq!(Field::from_header(name, &EN), Some(field));
+        }
+    }
+
+    #[test]
+    fn header_map_requires_id_and_name() {
+        let missing = HeaderMap::new(&header(&["Task Name", "Bucket Name"]), &EN);
+        assert!(matches!(missing, Err(ParseError::MissingColumn(Field::Id))));
+        let missing = HeaderMap::new(&header(&["Task ID", "Bucket Name"]), &EN);
+        assert!(matches!(
+            missing,
+            Err(ParseError::MissingColumn(Field::Name))
+        ));
+        assert!(HeaderMap::new(&header(&["Task Name", "Task ID"]), &EN).is_ok());
+    }
+
+    #[test]
+    fn header_map_missing_and_extra_columns() {
+        let map = HeaderMap::new(&header(&["Sprint", "Task Name", "Task ID", ""]), &EN).unwrap();
+        let missing: Vec<Field> = map.missing().collect();
+        assert_eq!(missing.len(), HEADERS_LEN - 2);
+        assert!(missing.contains(&Field::CreateDate));
+        assert!(!missing.contains(&Field::Id));
+
+        let row = header(&["7", "Write tests", "abc", "ignored"]);
+        let mut warnings = Vec::new();
+        let task = Task::parse(&row, 2, &map, &EN, &mut warnings).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            (task.id.as_str(), task.name.as_str()),
+            ("abc", "Write tests")
+        );
+        assert_eq!(task.extra.len(), 1);
+        assert_eq!(task.extra["Sprint"], "7");
+    }
+
+    #[test]
+    fn checklist_counts() {
+        assert_eq!(to_usizes(""), Ok(None));
+        assert_eq!(to_usizes("2/5"), Ok(Some((2, 5))));
+        assert_eq!(to_usizes(" 0 / 3 "), Ok(Some((0, 3))));
+        assert_eq!(to_usizes("4/4"), Ok(Some((4, 4))));
+        for bad in ["5/2", "3", "a/b", "1/", "/1", "-1/2"] {
+            assert_eq!(to_usizes(bad), Err(()), "{bad}");
+        }
     }
 }
@@ -0,0 +1,61 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{AnyResult, SimpleError};
+
+pub const APP_DIR: &str = "ms_planner_viewer";
+pub const PLAN_FILE: &str = "plan.xlsx";
+pub const CONFIG_FILE: &str = "config.toml";
+pub const HISTORY_DIR: &str = "history";
+
+/// Where the plan and config live for this run.
+#[derive(Debug, Clone)]
+pub struct Paths {
+    pub plan: PathBuf,
+    pub config: PathBuf,
+}
+impl Paths {
+    /// Uses the given paths, falling back to the XDG base directories.
+    pub fn resolve(plan: Option<PathBuf>, config: Option<PathBuf>) -> AnyResult<Self> {
+        let plan = match plan {
+            Some(plan) => plan,
+            None => data_dir()?.join(PLAN_FILE),
+        };
+        let config = match config {
+            Some(config) => config,
+            None => config_dir()?.join(CONFIG_FILE),
+        };
+        Ok(Self { plan, config })
+    }
+    /// Archive of every export that was loaded, see `history`. Only looked up
+    /// when needed, so commands given both paths run without a data directory.
+    pub fn history(&self) -> io::Result<PathBuf> {
+        let dir = data_dir().map_err(io::Error::other)?;
+        Ok(dir.join(HISTORY_DIR))
+    }
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> Result<PathBuf, SimpleError> {
+    let base = match std::env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(home_fallback))
+            .ok_or_else(|| format!("neither ${var} nor $HOME is set"))?,
+    };
+    Ok(base.join(APP_DIR))
+}
+/// `$XDG_CONFIG_HOME/ms_planner_viewer`, defaulting to `~/.config`.
+pub fn config_dir() -> Result<PathBuf, SimpleError> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+/// `$XDG_DATA_HOME/ms_planner_viewer`, defaulting to `~/.local/share`.
+pub fn data_dir() -> Result<PathBuf, SimpleError> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+/// Replaces a leading `~` with `$HOME`.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
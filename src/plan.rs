@@ -1,11 +1,14 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::str::FromStr;
 
-use crate::{SimpleError, lang};
+use crate::SimpleError;
+use crate::config::DateFilter;
+use crate::lang::{self, Locale};
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Plan {
     pub id: String,
     pub name: String,
@@ -36,16 +39,25 @@ impl FromStr for Progress {
         }
     }
 }
-impl From<&str> for Progress {
-    fn from(value: &str) -> Self {
+impl Progress {
+    pub fn from_locale(value: &str, locale: &Locale) -> Option<Self> {
         match value {
-            lang::nl::PROGRESS_NOT_STARTED => Self::NotStarted,
-            lang::nl::PROGRESS_ONGOING => Self::Ongoing,
-            lang::nl::PROGRESS_DONE => Self::Done,
-            _ => panic!("val: {value:?}"),
+            v if v == locale.progress_not_started => Some(Self::NotStarted),
+            v if v == locale.progress_ongoing => Some(Self::Ongoing),
+            v if v == locale.progress_done => Some(Self::Done),
+            _ => None,
         }
     }
 }
+impl TryFrom<&str> for Progress {
+    type Error = SimpleError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        lang::ALL
+            .into_iter()
+            .find_map(|l| Self::from_locale(value, l))
+            .ok_or_else(|| format!("not a valid progress in any language: {value}").into())
+    }
+}
 impl Display for Progress {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -81,17 +93,26 @@ impl Display for Priority {
         write!(f, "{self:?}")
     }
 }
-impl From<&str> for Priority {
-    fn from(value: &str) -> Self {
+impl Priority {
+    pub fn from_locale(value: &str, locale: &Locale) -> Option<Self> {
         match value {
-            lang::nl::PRIO_IMPORTANT => Self::Important,
-            lang::nl::PRIO_MID => Self::Mid,
-            lang::nl::PRIO_LOW => Self::Low,
-            lang::nl::PRIO_URGENT => Self::Urgent,
-            _ => panic!("val: {value:?}"),
+            v if v == locale.prio_important => Some(Self::Important),
+            v if v == locale.prio_mid => Some(Self::Mid),
+            v if v == locale.prio_low => Some(Self::Low),
+            v if v == locale.prio_urgent => Some(Self::Urgent),
+            _ => None,
         }
     }
 }
+impl TryFrom<&str> for Priority {
+    type Error = SimpleError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        lang::ALL
+            .into_iter()
+            .find_map(|l| Self::from_locale(value, l))
+            .ok_or_else(|| format!("not a valid priority in any language: {value}").into())
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Column {
@@ -107,9 +128,69 @@ pub enum Column {
     Labels,
     AssignedTo,
     Description,
+    CreatedBy,
+    Late,
+    Items,
+}
+impl Column {
+    pub fn items() -> Vec<Self> {
+        use Column as C;
+        vec![
+            C::Name,
+            C::Priority,
+            C::CreateDate,
+            C::StartDate,
+            C::Deadline,
+            C::CompleteDate,
+            C::Progress,
+            C::Bucket,
+            C::Labels,
+            C::AssignedTo,
+            C::Description,
+            C::CreatedBy,
+            C::Late,
+            C::Items,
+        ]
+    }
+}
+impl Column {
+    /// Name of the `Task` field the column shows.
+    pub fn field_name(&self) -> &'static str {
+        use Column as C;
+        match self {
+            C::Name => "name",
+            C::Priority => "priority",
+            C::CreateDate => "create_date",
+            C::StartDate => "start_date",
+            C::Deadline => "deadline",
+            C::CompleteDate => "complete_date",
+            C::Progress => "progress",
+            C::Bucket => "bucket",
+            C::Labels => "labels",
+            C::AssignedTo => "assigned_to",
+            C::Description => "description",
+            C::CreatedBy => "created_by",
+            C::Late => "late",
+            C::Items => "items_completed",
+        }
+    }
+}
+impl FromStr for Column {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::items()
+            .into_iter()
+            .find(|c| c.to_string().eq_ignore_ascii_case(s) || c.field_name() == s)
+            .ok_or_else(|| format!("not a valid column: {s}").into())
+    }
+}
+impl Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
     pub name: String,
@@ -129,4 +210,12 @@ pub struct Task {
     pub items: Vec<String>,
     pub labels: Vec<String>,
     pub description: String,
+    /// Columns of the export that don't map to a field, by header name.
+    pub extra: BTreeMap<String, String>,
+}
+impl Task {
+    /// Unfinished and past its deadline, the one definition the views count by.
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.progress != Progress::Done && DateFilter::Overdue.filter_at(self.deadline, today)
+    }
 }
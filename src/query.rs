@@ -0,0 +1,657 @@
+//! A small boolean query language over tasks, e.g.
+//! `bucket:"Backend" and (label:bug or priority>=Important) and not assigned:Jan and deadline<+7d`.
+//!
+//! Terms are `field op value` with op one of `: = != < <= > >=`. For text and
+//! list fields `:` matches a case insensitive substring and `=` the whole
+//! value. Dates compare against `YYYY-MM-DD`, `today` or an offset from today
+//! like `+7d`, `-2w` or `+1m`, and `:` takes a date filter such as `overdue`,
+//! which on the deadline only matches unfinished tasks.
+//! Terms combine with `and`, `or`, `not` and parentheses; `and` may be left out.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{Months, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{Priority, Progress, SimpleError, Task, config::DateFilter};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Field, Op, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Id,
+    Name,
+    Description,
+    Bucket,
+    Progress,
+    Priority,
+    Label,
+    Assigned,
+    CreatedBy,
+    Created,
+    Start,
+    Deadline,
+    Completed,
+    Late,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Contains,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Query {
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_at(task, chrono::Local::now().date_naive())
+    }
+    pub fn matches_at(&self, task: &Task, today: NaiveDate) -> bool {
+        self.expr.eval(task, today)
+    }
+}
+impl FromStr for Query {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!("unexpected {token}").into());
+        }
+        // validate the values now so evaluating can't fail
+        expr.check()?;
+        Ok(Self {
+            source: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+impl TryFrom<String> for Query {
+    type Error = SimpleError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+impl From<Query> for String {
+    fn from(value: Query) -> Self {
+        value.source
+    }
+}
+
+impl FromStr for Field {
+    type Err = SimpleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "id" => Ok(Self::Id),
+            "name" => Ok(Self::Name),
+            "description" | "desc" => Ok(Self::Description),
+            "bucket" => Ok(Self::Bucket),
+            "progress" => Ok(Self::Progress),
+            "priority" | "prio" => Ok(Self::Priority),
+            "label" | "labels" => Ok(Self::Label),
+            "assigned" | "assignee" | "assigned_to" => Ok(Self::Assigned),
+            "created_by" | "creator" => Ok(Self::CreatedBy),
+            "created" | "create_date" => Ok(Self::Created),
+            "start" | "start_date" => Ok(Self::Start),
+            "deadline" | "due" => Ok(Self::Deadline),
+            "completed" | "complete_date" => Ok(Self::Completed),
+            "late" => Ok(Self::Late),
+            _ => Err(format!("unknown field: {s}").into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    Open,
+    Close,
+}
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(w) => write!(f, "{w:?}"),
+            Self::Quoted(w) => write!(f, "\"{w}\""),
+            Self::Op(op) => write!(f, "{op:?}"),
+            Self::Open => write!(f, "'('"),
+            Self::Close => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, SimpleError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated quote".into()),
+                    }
+                }
+                tokens.push(Token::Quoted(word));
+            }
+            ':' | '=' | '!' | '<' | '>' => {
+                chars.next();
+                let eq = chars.next_if_eq(&'=').is_some();
+                let op = match (c, eq) {
+                    (':', false) => Op::Contains,
+                    ('=', false) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    _ => return Err(format!("unknown operator after {c}").into()),
+                };
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| {
+                    !c.is_whitespace()
+                        && !matches!(c, '(' | ')' | '"' | ':' | '=' | '!' | '<' | '>')
+                }) {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Deepest nesting of parentheses and `not`, so parsing and evaluating
+/// can't run out of stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Parentheses and `not` around the current token.
+    depth: usize,
+}
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn or(&mut self) -> Result<Expr, SimpleError> {
+        let mut exprs = vec![self.and()?];
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            exprs.push(self.and()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expr::Or(exprs),
+        })
+    }
+    fn and(&mut self) -> Result<Expr, SimpleError> {
+        let mut exprs = vec![self.unary()?];
+        loop {
+            if self.peek_keyword("and") {
+                self.pos += 1;
+            } else if self.pos >= self.tokens.len()
+                || self.peek_keyword("or")
+                || self.tokens[self.pos] == Token::Close
+            {
+                break;
+            }
+            exprs.push(self.unary()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expr::And(exprs),
+        })
+    }
+    /// Parses what is nested in a `not` or parentheses one level deeper.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> Result<T, SimpleError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("query nested deeper than {MAX_DEPTH} levels").into());
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        Ok(parsed)
+    }
+    fn unary(&mut self) -> Result<Expr, SimpleError> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.nested(Self::unary)??)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.nested(Self::or)??;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("missing ')'".into()),
+                }
+            }
+            Some(Token::Word(field)) => {
+                let field: Field = field.parse()?;
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    _ => return Err(format!("expected an operator after {field:?}").into()),
+                };
+                let value = match self.next() {
+                    Some(Token::Word(v)) | Some(Token::Quoted(v)) => v,
+                    _ => return Err(format!("expected a value after {field:?}").into()),
+                };
+                Ok(Expr::Term(field, op, value))
+            }
+            Some(token) => Err(format!("unexpected {token}").into()),
+            None => Err("unexpected end of query".into()),
+        }
+    }
+}
+
+fn priority_rank(p: &Priority) -> u8 {
+    match p {
+        Priority::Low => 0,
+        Priority::Mid => 1,
+        Priority::Important => 2,
+        Priority::Urgent => 3,
+    }
+}
+fn progress_rank(p: &Progress) -> u8 {
+    match p {
+        Progress::NotStarted => 0,
+        Progress::Ongoing => 1,
+        Progress::Done => 2,
+    }
+}
+fn parse_priority(s: &str) -> Result<Priority, SimpleError> {
+    match s.to_lowercase().as_str() {
+        "low" => Ok(Priority::Low),
+        "mid" | "medium" => Ok(Priority::Mid),
+        "important" => Ok(Priority::Important),
+        "urgent" => Ok(Priority::Urgent),
+        _ => Err(format!("not a valid priority: {s}").into()),
+    }
+}
+fn parse_progress(s: &str) -> Result<Progress, SimpleError> {
+    match s.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+        "notstarted" => Ok(Progress::NotStarted),
+        "ongoing" | "inprogress" => Ok(Progress::Ongoing),
+        "done" | "completed" => Ok(Progress::Done),
+        _ => Err(format!("not a valid progress: {s}").into()),
+    }
+}
+/// `YYYY-MM-DD`, `today` or a signed offset from today in days, weeks or months.
+fn parse_date(s: &str, today: NaiveDate) -> Result<NaiveDate, SimpleError> {
+    let err = || SimpleError::from(format!("not a valid date: {s}"));
+    if s.eq_ignore_ascii_case("today") {
+        return Ok(today);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    let (sign, rest) = match s.chars().next() {
+        Some('+') => (1, &s[1..]),
+        Some('-') => (-1, &s[1..]),
+        _ => return Err(err()),
+    };
+    let unit = rest.chars().last().ok_or_else(err)?;
+    let n: i64 = rest[..rest.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| err())?;
+    let out_of_range = || SimpleError::from("date offset out of range");
+    let date = match unit {
+        'd' => TimeDelta::try_days(sign * n).and_then(|d| today.checked_add_signed(d)),
+        'w' => TimeDelta::try_weeks(sign * n).and_then(|d| today.checked_add_signed(d)),
+        'm' => {
+            let months = Months::new(u32::try_from(n).map_err(|_| out_of_range())?);
+            match sign {
+                1 => today.checked_add_months(months),
+                _ => today.checked_sub_months(months),
+            }
+        }
+        _ => return Err(err()),
+    };
+    date.ok_or_else(out_of_range)
+}
+
+fn compare<T: Ord>(a: T, op: Op, b: T) -> bool {
+    match op {
+        Op::Contains | Op::Eq => a == b,
+        Op::Ne => a != b,
+        Op::Lt => a < b,
+        Op::Le => a <= b,
+        Op::Gt => a > b,
+        Op::Ge => a >= b,
+    }
+}
+fn text_matches(text: &str, op: Op, value: &str) -> bool {
+    match op {
+        Op::Contains => text.to_lowercase().contains(&value.to_lowercase()),
+        Op::Ne => !text_matches(text, Op::Eq, value),
+        _ => compare(text.to_lowercase(), op, value.to_lowercase()),
+    }
+}
+fn list_matches(list: &[String], op: Op, value: &str) -> bool {
+    match op {
+        Op::Ne => !list.iter().any(|s| text_matches(s, Op::Eq, value)),
+        _ => list.iter().any(|s| text_matches(s, op, value)),
+    }
+}
+
+impl Expr {
+    fn check(&self) -> Result<(), SimpleError> {
+        let today = NaiveDate::default();
+        match self {
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().try_for_each(Self::check),
+            Self::Not(a) => a.check(),
+            Self::Term(field, op, value) => match field {
+                Field::Progress => parse_progress(value).map(|_| ()),
+                Field::Priority => parse_priority(value).map(|_| ()),
+                Field::Created | Field::Start | Field::Deadline | Field::Completed => match op {
+                    Op::Contains => value.parse::<DateFilter>().map(|_| ()),
+                    _ => parse_date(value, today).map(|_| ()),
+                },
+                Field::Late => value
+                    .parse::<bool>()
+                    .map(|_| ())
+                    .map_err(|_| format!("not true or false: {value}").into()),
+                _ => Ok(()),
+            },
+        }
+    }
+    pub fn eval(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::And(exprs) => exprs.iter().all(|e| e.eval(task, today)),
+            Self::Or(exprs) => exprs.iter().any(|e| e.eval(task, today)),
+            Self::Not(a) => !a.eval(task, today),
+            Self::Term(field, op, value) => {
+                let date = |date: Option<NaiveDate>| match op {
+                    Op::Contains => value
+                        .parse::<DateFilter>()
+                        .is_ok_and(|df| df.filter_at(date, today)),
+                    _ => match (date, parse_date(value, today)) {
+                        (Some(date), Ok(value)) => compare(date, *op, value),
+                        _ => false,
+                    },
+                };
+                match field {
+                    Field::Id => text_matches(&task.id, *op, value),
+                    Field::Name => text_matches(&task.name, *op, value),
+                    Field::Description => text_matches(&task.description, *op, value),
+                    Field::Bucket => text_matches(&task.bucket, *op, value),
+                    Field::CreatedBy => text_matches(&task.created_by, *op, value),
+                    Field::Label => list_matches(&task.labels, *op, value),
+                    Field::Assigned => list_matches(&task.assigned_to, *op, value),
+                    Field::Progress => parse_progress(value).is_ok_and(|v| {
+                        compare(progress_rank(&task.progress), *op, progress_rank(&v))
+                    }),
+                    Field::Priority => parse_priority(value).is_ok_and(|v| {
+                        compare(priority_rank(&task.priority), *op, priority_rank(&v))
+                    }),
+                    Field::Created => date(Some(task.create_date)),
+                    Field::Start => date(task.start_date),
+                    Field::Deadline if *op == Op::Contains => value
+                        .parse::<DateFilter>()
+                        .is_ok_and(|df| df.filter_deadline(task, today)),
+                    Field::Deadline => date(task.deadline),
+                    Field::Completed => date(task.complete_date),
+                    Field::Late => value.parse().is_ok_and(|v| compare(task.late, *op, v)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_offsets() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let date = |s| parse_date(s, today);
+        assert_eq!(
+            date("+3d").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        );
+        assert_eq!(
+            date("-2w").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 3).unwrap()
+        );
+        assert_eq!(
+            date("+1m").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 11, 17).unwrap()
+        );
+    }
+
+    #[test]
+    fn date_offset_out_of_range() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        for offset in [
+            "+100000000d",
+            "-100000000w",
+            "+99999999999m",
+            "+9223372036854775807d",
+        ] {
+            let err = parse_date(offset, today).unwrap_err();
+            assert_eq!(err.to_string(), "date offset out of range");
+        }
+        assert!("deadline<+100000000d".parse::<Query>().is_err());
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+    fn task() -> Task {
+        Task {
+            id: "T1".into(),
+            name: "Fix login bug".into(),
+            description: "Crash on start".into(),
+            bucket: "Über".into(),
+            progress: Progress::Ongoing,
+            priority: Priority::Important,
+            labels: vec!["bug".into(), "ui".into()],
+            assigned_to: vec!["Ann Smet".into()],
+            created_by: "Jan".into(),
+            create_date: day(1),
+            start_date: Some(day(10)),
+            deadline: Some(day(20)),
+            ..Default::default()
+        }
+    }
+    fn expr(s: &str) -> Expr {
+        s.parse::<Query>().unwrap().expr
+    }
+    fn term(field: Field, op: Op, value: &str) -> Expr {
+        Expr::Term(field, op, value.into())
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let (a, b, c) = (
+            term(Field::Id, Op::Contains, "a"),
+            term(Field::Id, Op::Contains, "b"),
+            term(Field::Id, Op::Contains, "c"),
+        );
+        assert_eq!(
+            expr("id:a or id:b and id:c"),
+            Expr::Or(vec![a.clone(), Expr::And(vec![b.clone(), c.clone()])])
+        );
+        assert_eq!(
+            expr("(id:a or id:b) and id:c"),
+            Expr::And(vec![Expr::Or(vec![a.clone(), b.clone()]), c.clone()])
+        );
+        assert_eq!(expr("id:a OR id:b OR id:c"), Expr::Or(vec![a, b, c]));
+    }
+
+    #[test]
+    fn implicit_and() {
+        assert_eq!(expr("id:a id:b"), expr("id:a and id:b"));
+        assert_eq!(expr("id:a (id:b or id:c)"), expr("id:a and (id:b or id:c)"));
+    }
+
+    #[test]
+    fn not() {
+        let a = term(Field::Id, Op::Contains, "a");
+        assert_eq!(expr("not id:a"), Expr::Not(Box::new(a.clone())));
+        assert_eq!(
+            expr("not id:a and id:b"),
+            Expr::And(vec![
+                Expr::Not(Box::new(a.clone())),
+                term(Field::Id, Op::Contains, "b")
+            ])
+        );
+        assert_eq!(
+            expr("not not id:a"),
+            Expr::Not(Box::new(Expr::Not(Box::new(a))))
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(
+            expr(r#"name:"login bug""#),
+            term(Field::Name, Op::Contains, "login bug")
+        );
+        assert_eq!(
+            expr(r#"name="say \"hi\"""#),
+            term(Field::Name, Op::Eq, r#"say "hi""#)
+        );
+        assert_eq!(
+            expr(r#"label:"or""#),
+            term(Field::Label, Op::Contains, "or")
+        );
+        assert!(r#"name:"open"#.parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn fields_and_ops() {
+        let task = task();
+        let today = day(17);
+        let cases = [
+            ("id=t1", true),
+            ("id!=T1", false),
+            ("name:LOGIN", true),
+            ("name=login", false),
+            ("desc:crash", true),
+            ("bucket=über", true),
+            ("bucket!=über", false),
+            ("bucket!=Backend", true),
+            ("bucket>a", true),
+            ("progress=ongoing", true),
+            ("progress<done", true),
+            ("progress>=in-progress", true),
+            ("priority>=Important", true),
+            ("priority>important", false),
+            ("prio<=mid", false),
+            ("label:bu", true),
+            ("label=bug", true),
+            ("label!=ui", false),
+            ("label!=docs", true),
+            ("assigned:ann", true),
+            ("assignee=\"ann smet\"", true),
+            ("creator=jan", true),
+            ("created<2026-10-02", true),
+            ("created>today", false),
+            ("start<=+0d", true),
+            ("start>=-1w", true),
+            ("deadline<+7d", true),
+            ("deadline>=+1m", false),
+            ("deadline:\"next 3 days\"", true),
+            ("deadline:overdue", false),
+            ("due:\"this week\"", false),
+            ("completed:none", true),
+            ("completed<today", false),
+            ("late=false", true),
+            ("late!=false", false),
+        ];
+        for (query, expected) in cases {
+            let matched = query.parse::<Query>().unwrap().matches_at(&task, today);
+            assert_eq!(matched, expected, "{query}");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        for query in [
+            "",
+            "owner:x",
+            "id:",
+            "id a",
+            "(id:a",
+            "id:a)",
+            "priority=huge",
+            "progress=stuck",
+            "deadline<soon",
+            "deadline:someday",
+            "late=maybe",
+            "id~a",
+        ] {
+            assert!(query.parse::<Query>().is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let deep = format!("{}id:a{}", "(".repeat(1000), ")".repeat(1000));
+        assert!(deep.parse::<Query>().is_err());
+        assert!("not ".repeat(1000).parse::<Query>().is_err());
+        let ok = format!("{}id:a{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert!(ok.parse::<Query>().is_ok());
+    }
+
+    #[test]
+    fn long_chains_stay_flat() {
+        let chain = vec!["id:x"; 50_000].join(" or ");
+        let query: Query = chain.parse().unwrap();
+        assert!(!query.matches_at(&task(), day(17)));
+    }
+}
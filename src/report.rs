@@ -0,0 +1,193 @@
+//! A single HTML file summing up the plan, with inline styles and no
+//! external assets so it can be mailed around.
+use std::io::Write;
+
+use chrono::NaiveDate;
+
+use crate::{Plan, Progress, Task, config, dashboard::Stats, workload};
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0; }
+.meta { color: #666; margin-top: 0.3em; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+th { background: #f0f0f0; }
+td.num { text-align: right; }
+.late { color: #c0392b; font-weight: bold; }
+.bar { display: inline-block; width: 6em; height: 0.7em; background: #ddd; }
+.bar > span { display: block; height: 100%; background: #27ae60; }
+.cards { display: flex; gap: 1em; }
+.card { border: 1px solid #ccc; padding: 0.6em 1em; min-width: 6em; }
+.card b { display: block; font-size: 1.6em; }
+";
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn date(d: Option<NaiveDate>) -> String {
+    d.map(|d| d.to_string()).unwrap_or_default()
+}
+
+fn items_bar(task: &Task) -> String {
+    match task.items_completed {
+        Some((done, all)) if all > 0 => format!(
+            r#"<span class="bar"><span style="width: {}%"></span></span> {done}/{all}"#,
+            done * 100 / all
+        ),
+        _ => String::new(),
+    }
+}
+
+fn task_link(task: &Task, plan_id: &str) -> String {
+    format!(
+        r#"<a href="{}">{}</a>"#,
+        escape(&task.to_url(plan_id)),
+        escape(&task.name)
+    )
+}
+
+/// Writes the report of `tasks`, which are usually the filtered tasks of `plan`.
+pub fn write_report(
+    out: &mut impl Write,
+    plan: &Plan,
+    tasks: &[Task],
+    today: NaiveDate,
+) -> std::io::Result<()> {
+    let stats = Stats::new(tasks, today);
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, r#"<html lang="en"><head><meta charset="utf-8">"#)?;
+    writeln!(out, "<title>{}</title>", escape(&plan.name))?;
+    writeln!(out, "<style>\n{STYLE}</style></head><body>")?;
+    writeln!(out, "<h1>{}</h1>", escape(&plan.name))?;
+    writeln!(
+        out,
+        r#"<p class="meta">{} of {} tasks, exported {}, report of {today}</p>"#,
+        tasks.len(),
+        plan.tasks.len(),
+        plan.export_date
+    )?;
+
+    writeln!(out, r#"<div class="cards">"#)?;
+    let done = tasks
+        .iter()
+        .filter(|t| t.progress == Progress::Done)
+        .count();
+    for (label, count) in [
+        ("tasks", tasks.len() as u64),
+        ("open", stats.open),
+        ("done", done as u64),
+        ("overdue", stats.overdue),
+    ] {
+        writeln!(out, r#"<div class="card"><b>{count}</b>{label}</div>"#)?;
+    }
+    writeln!(out, "</div>")?;
+
+    writeln!(out, "<h2>Buckets</h2>\n<table>")?;
+    write!(out, "<tr><th>Bucket</th>")?;
+    for progress in Progress::items() {
+        write!(out, "<th>{progress}</th>")?;
+    }
+    writeln!(out, "<th>Total</th></tr>")?;
+    for (bucket, counts) in &stats.buckets {
+        write!(out, "<tr><td>{}</td>", escape(bucket))?;
+        for count in counts {
+            write!(out, r#"<td class="num">{count}</td>"#)?;
+        }
+        let total: u64 = counts.iter().sum();
+        writeln!(out, r#"<td class="num">{total}</td></tr>"#)?;
+    }
+    writeln!(out, "</table>")?;
+
+    let mut overdue: Vec<&Task> = tasks.iter().filter(|t| t.is_overdue(today)).collect();
+    overdue.sort_by_key(|t| t.deadline);
+    writeln!(out, "<h2>Overdue ({})</h2>", overdue.len())?;
+    if overdue.is_empty() {
+        writeln!(out, "<p>Nothing is overdue.</p>")?;
+    } else {
+        writeln!(
+            out,
+            "<table>\n<tr><th>Task</th><th>Bucket</th><th>Deadline</th><th>Days late</th><th>Assigned to</th></tr>"
+        )?;
+        for task in overdue {
+            let deadline = task.deadline.unwrap_or(today);
+            writeln!(
+                out,
+                r#"<tr><td>{}</td><td>{}</td><td>{deadline}</td><td class="num late">{}</td><td>{}</td></tr>"#,
+                task_link(task, &plan.id),
+                escape(&task.bucket),
+                (today - deadline).num_days(),
+                escape(&task.assigned_to.join(", "))
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    writeln!(out, "<h2>Assignees</h2>")?;
+    let mut people = config::get_unique_strings(tasks.iter().flat_map(|t| &t.assigned_to));
+    people.sort_by_key(|p| p.to_lowercase());
+    for load in workload::workloads(tasks, &people, today) {
+        let summary = format!(
+            "{} open, {} overdue, {} done",
+            load.not_started + load.ongoing,
+            load.overdue,
+            load.done
+        );
+        let tasks = tasks
+            .iter()
+            .filter(|t| t.assigned_to.contains(&load.person));
+        write_task_table(out, &plan.id, &load.person, &summary, tasks, today)?;
+    }
+    let unassigned: Vec<&Task> = tasks.iter().filter(|t| t.assigned_to.is_empty()).collect();
+    if !unassigned.is_empty() {
+        let open = unassigned.iter().filter(|t| t.progress != Progress::Done);
+        let summary = format!("{} open", open.count());
+        let tasks = unassigned.into_iter();
+        write_task_table(out, &plan.id, "(unassigned)", &summary, tasks, today)?;
+    }
+    writeln!(out, "</body></html>")
+}
+
+/// The tasks of one person, open ones first and by deadline.
+fn write_task_table<'a>(
+    out: &mut impl Write,
+    plan_id: &str,
+    title: &str,
+    summary: &str,
+    tasks: impl Iterator<Item = &'a Task>,
+    today: NaiveDate,
+) -> std::io::Result<()> {
+    let mut tasks: Vec<&Task> = tasks.collect();
+    tasks.sort_by_key(|t| {
+        (
+            t.progress == Progress::Done,
+            t.deadline.is_none(),
+            t.deadline,
+        )
+    });
+    writeln!(out, "<h3>{} <small>{summary}</small></h3>", escape(title))?;
+    writeln!(
+        out,
+        "<table>\n<tr><th>Task</th><th>Bucket</th><th>Progress</th><th>Priority</th><th>Deadline</th><th>Checklist</th></tr>"
+    )?;
+    for task in tasks {
+        let deadline = match task.is_overdue(today) {
+            true => format!(r#"<span class="late">{}</span>"#, date(task.deadline)),
+            false => date(task.deadline),
+        };
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{deadline}</td><td>{}</td></tr>",
+            task_link(task, plan_id),
+            escape(&task.bucket),
+            task.progress,
+            task.priority,
+            items_bar(task)
+        )?;
+    }
+    writeln!(out, "</table>")
+}
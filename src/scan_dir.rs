@@ -1,6 +1,10 @@
+use std::collections::HashSet;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{Plan, SimpleError, paths};
 
 pub fn move_file(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<bool> {
     let src = src.as_ref();
@@ -11,7 +15,7 @@ pub fn move_file(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<boo
     }
 
     match fs::rename(src, dst) {
-        Ok(_) => return Ok(true),
+        Ok(_) => Ok(true),
         Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
             if let Some(parent) = dst.parent() {
                 fs::create_dir_all(parent)?;
@@ -35,3 +39,167 @@ pub fn move_file(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<boo
         Err(e) => Err(e),
     }
 }
+
+/// Where new exports are taken from: one file, or the files in a directory
+/// whose name matches a glob like `~/Downloads/*.xlsx`.
+#[derive(Debug, Clone)]
+pub struct ScanPath {
+    dir: PathBuf,
+    pattern: glob::Pattern,
+    /// The path as configured, matched literally as well.
+    path: PathBuf,
+}
+impl ScanPath {
+    /// Relative paths are taken from the working directory, as the watcher
+    /// reports absolute ones.
+    pub fn new(path: &Path) -> Result<Self, SimpleError> {
+        let path = std::path::absolute(paths::expand_home(path))
+            .map_err(|e| format!("scan path {}: {e}", path.display()))?;
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("scan path has no file name: {}", path.display()))?;
+        let pattern =
+            glob::Pattern::new(name).map_err(|e| format!("scan path {}: {e}", path.display()))?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        Ok(Self { dir, pattern, path })
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    pub fn matches(&self, path: &Path) -> bool {
+        path == self.path
+            || path.parent() == Some(&self.dir)
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| self.pattern.matches(n))
+    }
+    /// The matching files with their modification time, newest first.
+    pub fn candidates(&self) -> io::Result<Vec<(PathBuf, SystemTime)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files: Vec<(PathBuf, SystemTime)> = fs::read_dir(&self.dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && self.matches(path))
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect();
+        files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        Ok(files)
+    }
+}
+impl std::fmt::Display for ScanPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())
+    }
+}
+
+/// Moves the newest matching export of the same plan over the plan file.
+pub struct Scanner {
+    pub scan_path: ScanPath,
+    plan_path: PathBuf,
+    /// Id of the plan at `plan_path`, read on the first scan that can.
+    plan_id: Option<String>,
+    /// Files that aren't exports of the plan, by path and modification time.
+    rejected: HashSet<(PathBuf, SystemTime)>,
+}
+impl Scanner {
+    pub fn new(scan_path: ScanPath, plan_path: PathBuf) -> Self {
+        Self {
+            scan_path,
+            plan_path,
+            plan_id: None,
+            rejected: HashSet::new(),
+        }
+    }
+    /// Only looks at files newer than the plan file, so older downloads left
+    /// in the directory never replace a newer export. Nothing is taken while
+    /// the id of the plan can't be read, as any export would match. Returns
+    /// whether the plan was replaced.
+    pub fn scan(&mut self) -> io::Result<bool> {
+        let plan_id = match &self.plan_id {
+            Some(id) => id.clone(),
+            None => {
+                let (plan, _) = Plan::from_path_lenient(&self.plan_path).map_err(|e| {
+                    io::Error::other(format!(
+                        "can't read the plan id from {}: {e}",
+                        self.plan_path.display()
+                    ))
+                })?;
+                self.plan_id = Some(plan.id.clone());
+                plan.id
+            }
+        };
+        let since = fs::metadata(&self.plan_path)
+            .and_then(|m| m.modified())
+            .ok();
+        for (path, modified) in self.scan_path.candidates()? {
+            if since.is_some_and(|since| modified <= since) {
+                break;
+            }
+            if self.rejected.contains(&(path.clone(), modified)) || !settled(&path) {
+                continue;
+            }
+            // parse warnings are reported when the plan is reloaded
+            match Plan::from_path_lenient(&path) {
+                Ok((plan, _)) if plan.id == plan_id => {
+                    return move_file(&path, &self.plan_path);
+                }
+                _ => {
+                    self.rejected.insert((path, modified));
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// The file must keep the same size this long before it is taken.
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+/// Whether the file exists and is done being written, going by its size
+/// staying the same.
+fn settled(path: &Path) -> bool {
+    let size = |path: &Path| fs::metadata(path).map(|m| m.len()).ok();
+    let Some(mut last) = size(path) else {
+        return false;
+    };
+    loop {
+        std::thread::sleep(SETTLE_TIME);
+        match size(path) {
+            None => return false,
+            // an empty file is still being created, or not an export at all
+            Some(size) if size == last => return size > 0,
+            Some(size) => last = size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_taken_without_a_plan_id() {
+        let dir = std::env::temp_dir().join(format!("scan_dir_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let download = dir.join("export.xlsx");
+        fs::write(&download, "not a plan").unwrap();
+        let plan = dir.join("plan.xlsx");
+        let scan_path = ScanPath::new(&dir.join("export*.xlsx")).unwrap();
+        let mut scanner = Scanner::new(scan_path, plan.clone());
+
+        assert!(scanner.scan().is_err());
+        assert!(download.exists());
+        assert!(!plan.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
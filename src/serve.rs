@@ -0,0 +1,179 @@
+//! Read-only JSON API over the plan, reloaded when the watcher moves in a
+//! new export.
+use std::sync::{Arc, RwLock};
+
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::{
+    AnyResult, Plan, Task,
+    app::{filter_tasks, sort_tasks},
+    config::Config,
+    dashboard::Stats,
+    event::{self, MsEvent},
+    history,
+    paths::Paths,
+    query::Query,
+};
+
+#[derive(thiserror::Error, Debug)]
+enum ApiError {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("only GET is supported")]
+    MethodNotAllowed,
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+impl ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            Self::NotFound(_) => 404,
+            Self::BadRequest(_) => 400,
+            Self::MethodNotAllowed => 405,
+            Self::Json(_) => 500,
+        }
+    }
+}
+
+/// Serves until the process is killed, answering each request from the
+/// latest plan.
+pub fn serve(addr: &str, plan: Plan, config: &Config, paths: &Paths) -> AnyResult<()> {
+    let server = Server::http(addr).map_err(|e| anyhow::anyhow!("binding {addr}: {e}"))?;
+    eprintln!("serving {} on http://{addr}", plan.name);
+    let plan = Arc::new(RwLock::new(plan));
+
+    let (events, _) = event::watch(config, paths);
+    let (reloaded, paths) = (plan.clone(), paths.clone());
+    std::thread::spawn(move || {
+        for event in events {
+            match event {
+                MsEvent::PlanMoved => reload(&reloaded, &paths),
+                MsEvent::WatchError(msg) => eprintln!("warning: {msg}"),
+                MsEvent::Crossterm(_) => {}
+            }
+        }
+    });
+
+    for request in server.incoming_requests() {
+        let result = match request.method() {
+            Method::Get => {
+                let plan = plan.read().unwrap_or_else(|e| e.into_inner());
+                route(request.url(), &plan, config)
+            }
+            _ => Err(ApiError::MethodNotAllowed),
+        };
+        let (status, body) = match result {
+            Ok(body) => (200, body),
+            Err(e) => (e.status(), json!({ "error": e.to_string() })),
+        };
+        let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("responding failed: {e}");
+        }
+    }
+    Ok(())
+}
+
+fn reload(plan: &RwLock<Plan>, paths: &Paths) {
+    match Plan::from_path_lenient(&paths.plan) {
+        Ok((new, warnings)) => {
+            for warning in &warnings {
+                eprintln!("warning: {warning}");
+            }
+            let archived = paths
+                .history()
+                .and_then(|dir| history::archive(&new, &paths.plan, &dir));
+            if let Err(e) = archived {
+                eprintln!("warning: archiving the plan failed: {e}");
+            }
+            eprintln!("plan reloaded, exported {}", new.export_date);
+            *plan.write().unwrap_or_else(|e| e.into_inner()) = new;
+        }
+        Err(e) => eprintln!("reloading the plan failed: {e}"),
+    }
+}
+
+fn route(url: &str, plan: &Plan, config: &Config) -> Result<Value, ApiError> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = parse_params(query);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["plan"] => Ok(serde_json::to_value(plan)?),
+        ["tasks"] => Ok(serde_json::to_value(tasks(plan, config, &params)?)?),
+        ["tasks", id] => {
+            let id = percent_decode(id);
+            let task = plan.tasks.iter().find(|t| t.id == id);
+            let task = task.ok_or_else(|| ApiError::NotFound(format!("task {id}")))?;
+            Ok(serde_json::to_value(task)?)
+        }
+        ["stats"] => {
+            let today = chrono::Local::now().date_naive();
+            let tasks = tasks(plan, config, &params)?;
+            Ok(serde_json::to_value(Stats::new(&tasks, today))?)
+        }
+        _ => Err(ApiError::NotFound(path.to_string())),
+    }
+}
+
+/// The tasks matching the `query` parameter, on top of the saved filter when
+/// `saved` is set, in the saved sort order.
+fn tasks(plan: &Plan, config: &Config, params: &[(String, String)]) -> Result<Vec<Task>, ApiError> {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, v)| v.as_str())
+    };
+    let mut config = config.clone();
+    if !matches!(param("saved"), Some("" | "1" | "true")) {
+        config.filter = Default::default();
+    }
+    if let Some(query) = param("query").filter(|q| !q.trim().is_empty()) {
+        let query: Query = query
+            .parse()
+            .map_err(|e| ApiError::BadRequest(format!("query: {e}")))?;
+        config.filter.query = Some(query);
+    }
+    let mut tasks = filter_tasks(&config, &plan.tasks);
+    sort_tasks(&config, &mut tasks);
+    Ok(tasks)
+}
+
+fn parse_params(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 2;
+            }
+            (b'+', _) => out.push(b' '),
+            (b, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -0,0 +1,233 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    Progress, Task,
+    app::App,
+    group::{self, GroupBy},
+};
+
+const LABEL_WIDTH: usize = 24;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Zoom {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+impl Zoom {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Day => Self::Week,
+            Self::Week => Self::Month,
+            Self::Month => Self::Day,
+        }
+    }
+    /// First day of the period the date falls in.
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => group::week_start(date),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+    fn add(&self, date: NaiveDate, periods: i64) -> NaiveDate {
+        match self {
+            Self::Day => date + Duration::days(periods),
+            Self::Week => date + Duration::weeks(periods),
+            Self::Month if periods < 0 => date - Months::new(periods.unsigned_abs() as u32),
+            Self::Month => date + Months::new(periods as u32),
+        }
+    }
+    /// Periods from `from` to `date`, negative when the date is earlier.
+    fn periods(&self, from: NaiveDate, date: NaiveDate) -> i64 {
+        let (from, date) = (self.period_start(from), self.period_start(date));
+        match self {
+            Self::Day => (date - from).num_days(),
+            Self::Week => (date - from).num_weeks(),
+            Self::Month => {
+                (date.year() as i64 * 12 + date.month0() as i64)
+                    - (from.year() as i64 * 12 + from.month0() as i64)
+            }
+        }
+    }
+}
+
+pub struct TimelineState {
+    /// Date of the leftmost column.
+    pub start: NaiveDate,
+    pub zoom: Zoom,
+    pub group: GroupBy,
+    /// First row shown.
+    pub scroll: usize,
+}
+impl Default for TimelineState {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        Self {
+            start: today - Duration::days(7),
+            zoom: Zoom::default(),
+            group: GroupBy::Bucket,
+            scroll: 0,
+        }
+    }
+}
+impl TimelineState {
+    pub fn scroll_time(&mut self, periods: i64) {
+        self.start = self.zoom.add(self.zoom.period_start(self.start), periods);
+    }
+    pub fn zoom(&mut self) {
+        self.zoom = self.zoom.next();
+        self.start = self.zoom.period_start(self.start);
+    }
+    pub fn toggle_group(&mut self) {
+        self.group = match self.group {
+            GroupBy::Bucket => GroupBy::Assignee,
+            _ => GroupBy::Bucket,
+        };
+        self.scroll = 0;
+    }
+    /// Shows today near the left edge.
+    pub fn go_to_today(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        self.start = self.zoom.add(self.zoom.period_start(today), -2);
+    }
+}
+
+/// The first and last day of the task bar, a single day when it only has a
+/// start date or a deadline.
+fn span(task: &Task) -> Option<(NaiveDate, NaiveDate)> {
+    match (task.start_date, task.deadline) {
+        (Some(start), Some(deadline)) => Some((start.min(deadline), deadline.max(start))),
+        (Some(day), None) | (None, Some(day)) => Some((day, day)),
+        (None, None) => None,
+    }
+}
+
+fn bar_style(task: &Task, today: NaiveDate) -> Style {
+    if task.progress == Progress::Done {
+        Style::new().fg(Color::Green)
+    } else if task.is_overdue(today) {
+        Style::new().fg(Color::LightRed)
+    } else if task.progress == Progress::Ongoing {
+        Style::new().fg(Color::Blue)
+    } else {
+        Style::new().fg(Color::Gray)
+    }
+}
+
+/// Joins the cells into spans, merging neighbours with the same style.
+fn cells_to_line(cells: Vec<(char, Style)>) -> Vec<Span<'static>> {
+    let mut spans: Vec<(String, Style)> = Vec::new();
+    for (c, style) in cells {
+        match spans.last_mut() {
+            Some((text, last)) if *last == style => text.push(c),
+            _ => spans.push((c.to_string(), style)),
+        }
+    }
+    spans
+        .into_iter()
+        .map(|(text, style)| Span::styled(text, style))
+        .collect()
+}
+
+fn label(text: &str) -> String {
+    let text: String = text.chars().take(LABEL_WIDTH - 1).collect();
+    format!("{text:LABEL_WIDTH$}")
+}
+
+pub fn render_timeline(app: &mut App, f: &mut Frame, area: Rect) {
+    let state = &app.timeline;
+    let today = chrono::Local::now().date_naive();
+    let tasks = &app.displayed_tasks;
+    let zoom = state.zoom;
+    let start = zoom.period_start(state.start);
+    let title = format!(
+        "timeline by {} per {:?} (h/l: scroll, j/k: rows, z: zoom, g: group, t: today)",
+        state.group, zoom
+    );
+    let block = Block::bordered().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let columns = (inner.width as usize).saturating_sub(LABEL_WIDTH);
+    let today_column = zoom.periods(start, today);
+    let is_today = |column: usize| column as i64 == today_column;
+
+    // axis with a label at the start of every labelled period
+    let mut axis = vec![' '; columns];
+    let mut column = 0;
+    while column < columns {
+        let date = zoom.add(start, column as i64);
+        let text = match zoom {
+            Zoom::Day if date.weekday().num_days_from_monday() == 0 => date.format("%m-%d"),
+            Zoom::Week if date.day() <= 7 => date.format("%b"),
+            Zoom::Month if date.month0().is_multiple_of(3) => date.format("%b %Y"),
+            _ => {
+                column += 1;
+                continue;
+            }
+        }
+        .to_string();
+        for (i, c) in text.chars().enumerate() {
+            if let Some(cell) = axis.get_mut(column + i) {
+                *cell = c;
+            }
+        }
+        column += text.chars().count() + 1;
+    }
+    let axis_cells = axis
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| match (is_today(i), c) {
+            (true, ' ') => ('▼', Style::new().yellow()),
+            (true, c) => (c, Style::new().yellow()),
+            (false, c) => (c, Style::new()),
+        })
+        .collect();
+    let mut lines = vec![Line::from_iter(
+        std::iter::once(Span::raw(label(""))).chain(cells_to_line(axis_cells)),
+    )];
+
+    let mut rows = Vec::new();
+    for group in group::groups(tasks, state.group) {
+        let spans: Vec<_> = group
+            .tasks
+            .iter()
+            .filter_map(|&i| Some((i, span(&tasks[i])?)))
+            .collect();
+        if spans.is_empty() {
+            continue;
+        }
+        let header = format!("{} ({})", group.title, spans.len());
+        rows.push(Line::from(header).add_modifier(Modifier::BOLD));
+        for (i, (first, last)) in spans {
+            let task = &tasks[i];
+            let (first, last) = (zoom.periods(start, first), zoom.periods(start, last));
+            let style = bar_style(task, today);
+            let cells = (0..columns)
+                .map(|column| {
+                    let c = column as i64;
+                    match (first <= c && c <= last, is_today(column)) {
+                        (true, _) => ('█', style),
+                        (false, true) => ('│', Style::new().yellow()),
+                        (false, false) => ('·', Style::new().dark_gray()),
+                    }
+                })
+                .collect();
+            let name = Span::raw(label(&task.name));
+            rows.push(Line::from_iter(
+                std::iter::once(name).chain(cells_to_line(cells)),
+            ));
+        }
+    }
+    app.timeline.scroll = app.timeline.scroll.min(rows.len().saturating_sub(1));
+    lines.extend(rows.into_iter().skip(app.timeline.scroll));
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -1,4 +1,6 @@
-use crate::{config::Config, filter};
+use crate::{
+    board, calendar, config::Config, dashboard, filter, flow, group::TableRow, timeline, workload,
+};
 use ratatui::{
     Frame,
     crossterm::style::Color,
@@ -9,21 +11,45 @@ use ratatui::{
 };
 
 use crate::{
-    Task,
+    Column, Task,
     app::{App, InputMode},
 };
 use style::palette::tailwind;
-const HEADERS_LEN: usize = 7;
-const DATE_CONSTRAINT: Constraint = Constraint::Length(10);
 
 pub fn view(app: &mut App, f: &mut Frame) {
     match app.input_mode {
         InputMode::TableRow => table::view(app, f, f.area()),
+        InputMode::Query(_) => {
+            let [table, query] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(f.area());
+            table::view(app, f, table);
+            render_query_line(app, f, query);
+        }
         InputMode::FilterMode => {
             let [filter, table] =
                 Layout::horizontal([Constraint::Fill(1), Constraint::Fill(2)]).areas(f.area());
             filter::render_filter_column(app, f, filter);
-            table::view(app, f, table);
+            match app.filter_return {
+                Some(InputMode::Board) => board::render_board(app, f, table),
+                _ => table::view(app, f, table),
+            }
+        }
+        InputMode::Diff => render_diff(app, f),
+        InputMode::Workload => workload::render_workload(app, f, f.area()),
+        InputMode::Dashboard => dashboard::render_dashboard(app, f, f.area()),
+        InputMode::Flow => flow::render_flow(app, f, f.area()),
+        InputMode::Timeline => timeline::render_timeline(app, f, f.area()),
+        InputMode::Calendar => {
+            calendar::render_calendar(app, f, f.area());
+            if let Some(i) = app.selected_task {
+                task::view(app, f, f.area(), i);
+            }
+        }
+        InputMode::Board => {
+            board::render_board(app, f, f.area());
+            if let Some(i) = app.selected_task {
+                task::view(app, f, f.area(), i);
+            }
         }
     }
     render_error_box(app, f);
@@ -48,6 +74,34 @@ fn render_error_box(app: &mut App, f: &mut Frame) {
         );
     }
 }
+fn render_query_line(app: &mut App, f: &mut Frame, area: Rect) {
+    if let InputMode::Query(input) = &app.input_mode {
+        let block = Block::bordered().title("query (enter: apply, esc: cancel)");
+        let width = area.width.saturating_sub(3) as usize;
+        let scroll = input.visual_scroll(width);
+        f.render_widget(
+            Paragraph::new(input.value())
+                .scroll((0, scroll as u16))
+                .block(block),
+            area,
+        );
+        f.set_cursor_position((
+            area.x + 1 + (input.visual_cursor().max(scroll) - scroll) as u16,
+            area.y + 1,
+        ));
+    }
+}
+fn render_diff(app: &mut App, f: &mut Frame) {
+    if let Some((diff, scroll)) = &app.diff {
+        let block = Block::bordered().title(format!("diff of {}", app.plan.name));
+        f.render_widget(
+            Paragraph::new(diff.to_string())
+                .block(block)
+                .scroll((*scroll, 0)),
+            f.area(),
+        );
+    }
+}
 fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::horizontal([horizontal])
         .flex(Flex::Center)
@@ -59,80 +113,121 @@ pub trait AsText {
     fn as_text(&self) -> Text<'_>;
 }
 pub mod table {
-    use chrono::{NaiveDate, NaiveDateTime};
+    use chrono::NaiveDate;
     use ratatui::{
         layout::Alignment,
         text::{Line, Span},
     };
 
     use super::*;
-    pub fn get_headers() -> [Text<'static>; HEADERS_LEN] {
-        [
-            "Name".into(),
-            "Bucket".into(),
-            "Pro".into(),
-            "Pri".into(),
-            "Items".into(),
-            "Created".into(),
-            "Deadline".into(),
-        ]
+    pub fn header(column: Column) -> &'static str {
+        use Column as C;
+        match column {
+            C::Name => "Name",
+            C::Bucket => "Bucket",
+            C::Progress => "Pro",
+            C::Priority => "Pri",
+            C::Items => "Items",
+            C::CreateDate => "Created",
+            C::StartDate => "Start",
+            C::Deadline => "Deadline",
+            C::CompleteDate => "Completed",
+            C::Labels => "Labels",
+            C::AssignedTo => "Assigned",
+            C::CreatedBy => "Creator",
+            C::Late => "Late",
+            C::Description => "Description",
+        }
     }
     pub fn view(app: &mut App, f: &mut Frame, area: Rect) {
-        let headers = Row::new(get_headers());
-        let rows = app
-            .displayed_tasks
-            .iter()
-            .map(|task| task_to_row(task, &app.config));
-        let cols: [Constraint; HEADERS_LEN] = [
-            Constraint::Fill(1),
-            Constraint::Length(15),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(5),
-            DATE_CONSTRAINT,
-            DATE_CONSTRAINT,
-        ];
+        let columns = &app.config.table.columns;
+        let headers = Row::new(columns.iter().map(|c| header(c.column)));
+        let groups = app.groups();
+        let rows = app.table_rows(groups.as_deref());
+        let today = chrono::Local::now().date_naive();
+        let rows = rows.into_iter().map(|row| match (row, &groups) {
+            (TableRow::Header(g), Some(groups)) => {
+                let group = &groups[g];
+                let collapsed = app.collapsed.contains(&group.title);
+                let marker = if collapsed { "▸" } else { "▾" };
+                let title = format!("{marker} {} ({})", group.title, group.tasks.len());
+                Row::new([Text::from(title)]).style(
+                    Style::new()
+                        .add_modifier(Modifier::BOLD)
+                        .bg(tailwind::SLATE.c800),
+                )
+            }
+            (TableRow::Task(i, _), _) => task_to_row(&app.displayed_tasks[i], &app.config, today),
+            (TableRow::Header(_), None) => Row::default(),
+        });
+        let cols = columns.iter().map(|c| match c.width {
+            Some(width) => Constraint::Length(width),
+            None => Constraint::Fill(1),
+        });
 
+        let mut block = Block::bordered().title("ms planner");
+        if let Some(group_by) = app.config.group_by {
+            block = block.title(format!("grouped by {group_by} (g: group, z/Z: fold)"));
+        }
+        if let Some(query) = &app.config.filter.query {
+            block = block.title_bottom(format!("query: {query}"));
+        }
+        if let Some(person) = &app.drill_down {
+            block = block.title(format!("assigned to {person} (esc: all)"));
+        }
+        if app.watch.is_paused() {
+            block = block.title(
+                Line::from("watching paused (W: resume)")
+                    .yellow()
+                    .right_aligned(),
+            );
+        }
         let table = Table::new(rows, cols)
             .header(headers)
             .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
-            .block(Block::bordered().title("ms planner"));
+            .block(block);
         f.render_stateful_widget(table, area, &mut app.table_state);
 
         if let Some(i) = app.selected_task {
             task::view(app, f, area, i);
         }
     }
-    fn task_to_row<'a>(task: &'a Task, config: &'a Config) -> Row<'a> {
+    fn task_to_row<'a>(task: &'a Task, config: &'a Config, today: NaiveDate) -> Row<'a> {
         let name: Text = task.name.clone().into();
         let name = if config.filter.ids.contains(&task.id) {
             name.fg(tailwind::ORANGE.c300)
         } else {
             name
         };
-        let cells: [Text; HEADERS_LEN] = [
-            name,
-            task.bucket.clone().into(),
-            task.progress.as_text(),
-            task.priority.as_text(),
-            complete_items_text(task.items_completed),
-            task.create_date.to_string().into(),
-            deadline_text(task.deadline),
-        ];
-        Row::new(cells)
-    }
-    fn deadline_text(deadeline: Option<NaiveDate>) -> Text<'static> {
-        let now = chrono::offset::Utc::now();
-        if let Some(d) = deadeline {
-            let deadline = NaiveDateTime::from(d).and_utc();
-            let text = Text::from(d.to_string());
-            if deadline > now {
-                text
-            } else {
-                text.light_red()
+        let date = |d: Option<NaiveDate>| Text::from(d.map(|d| d.to_string()).unwrap_or_default());
+        let cells = config.table.columns.iter().map(|c| {
+            use Column as C;
+            match c.column {
+                C::Name => name.clone(),
+                C::Bucket => task.bucket.clone().into(),
+                C::Progress => task.progress.as_text(),
+                C::Priority => task.priority.as_text(),
+                C::Items => complete_items_text(task.items_completed),
+                C::CreateDate => task.create_date.to_string().into(),
+                C::StartDate => date(task.start_date),
+                C::Deadline => deadline_text(task, today),
+                C::CompleteDate => date(task.complete_date),
+                C::Labels => task.labels.join(", ").into(),
+                C::AssignedTo => task.assigned_to.join(", ").into(),
+                C::CreatedBy => task.created_by.clone().into(),
+                C::Late if task.late => Text::from("late").light_red(),
+                C::Late => Text::default(),
+                C::Description => task.description.lines().next().unwrap_or_default().into(),
             }
-        } else {
-            Text::default()
+        });
+        Row::new(cells.collect::<Vec<_>>())
+    }
+    /// The deadline of `task`, red when the task is overdue.
+    pub fn deadline_text(task: &Task, today: NaiveDate) -> Text<'static> {
+        match task.deadline {
+            Some(d) if task.is_overdue(today) => Text::from(d.to_string()).light_red(),
+            Some(d) => Text::from(d.to_string()),
+            None => Text::default(),
         }
     }
     fn complete_items_text<'a>(items: Option<(usize, usize)>) -> Text<'a> {
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Text,
+    widgets::{Block, Row, Table},
+};
+
+use crate::{Progress, Task, app::App, config::DateFilter};
+
+/// What one person has on their plate.
+#[derive(Debug, Default)]
+pub struct Workload {
+    pub person: String,
+    pub not_started: usize,
+    pub ongoing: usize,
+    pub done: usize,
+    /// Unfinished tasks past their deadline.
+    pub overdue: usize,
+    /// Unfinished tasks due this week.
+    pub due_this_week: usize,
+    /// Checked and total checklist items over all tasks.
+    pub items: (usize, usize),
+}
+
+pub fn workloads(tasks: &[Task], people: &[String], today: NaiveDate) -> Vec<Workload> {
+    let mut people = people.to_vec();
+    people.sort_by_key(|p| p.to_lowercase());
+    people
+        .into_iter()
+        .map(|person| {
+            let mut load = Workload::default();
+            for task in tasks.iter().filter(|t| t.assigned_to.contains(&person)) {
+                match task.progress {
+                    Progress::NotStarted => load.not_started += 1,
+                    Progress::Ongoing => load.ongoing += 1,
+                    Progress::Done => load.done += 1,
+                }
+                if task.progress != Progress::Done {
+                    load.overdue += task.is_overdue(today) as usize;
+                    load.due_this_week +=
+                        DateFilter::ThisWeek.filter_at(task.deadline, today) as usize;
+                }
+                if let Some((done, all)) = task.items_completed {
+                    load.items.0 += done;
+                    load.items.1 += all;
+                }
+            }
+            load.person = person;
+            load
+        })
+        .collect()
+}
+
+pub fn render_workload(app: &mut App, f: &mut Frame, area: Rect) {
+    let today = chrono::Local::now().date_naive();
+    let people = &app.filter_view.unique_task_keys.people;
+    let loads = workloads(&app.workload_tasks(), people, today);
+    let headers = Row::new([
+        "Person",
+        "Not started",
+        "Ongoing",
+        "Done",
+        "Overdue",
+        "This week",
+        "Items",
+    ])
+    .add_modifier(Modifier::BOLD);
+    let count = |n: usize| Text::from(n.to_string());
+    let rows = loads.iter().map(|load| {
+        let overdue = match load.overdue {
+            0 => count(0),
+            n => count(n).light_red(),
+        };
+        let items = match load.items {
+            (_, 0) => Text::default(),
+            (done, all) => Text::from(format!("{done}/{all}")),
+        };
+        Row::new([
+            Text::from(load.person.clone()),
+            count(load.not_started),
+            count(load.ongoing),
+            count(load.done),
+            overdue,
+            count(load.due_this_week),
+            items,
+        ])
+    });
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(11),
+        Constraint::Length(8),
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(9),
+    ];
+    let block = Block::bordered().title("workload (enter: show tasks, esc: back)");
+    let table = Table::new(rows, widths)
+        .header(headers)
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(block);
+    f.render_stateful_widget(table, area, &mut app.workload);
+}